@@ -1,11 +1,11 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rand::prelude::SliceRandom;
 use rand::{RngCore, SeedableRng, thread_rng};
 use acquire::{Acquire, Options};
 
 fn run_game() {
     let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(thread_rng().next_u64());
-    let mut game = Acquire::new(rand_chacha::ChaCha8Rng::seed_from_u64(thread_rng().next_u64()), &Options::default());
+    let mut game = Acquire::new(&mut rand_chacha::ChaCha8Rng::seed_from_u64(thread_rng().next_u64()), &Options::default());
 
     loop {
         if game.is_terminated() {
@@ -19,8 +19,31 @@ fn run_game() {
     }
 }
 
+fn mid_game() -> Acquire {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+    let mut game = Acquire::from_seed(2, &Options::default()).with_logging();
+
+    for _ in 0..100 {
+        if game.is_terminated() {
+            break;
+        }
+
+        let actions = game.actions();
+        let action = actions.choose(&mut rng).expect("an action");
+
+        game = game.apply_action(action.clone());
+    }
+
+    game
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("fib 20", |b| b.iter(run_game));
+
+    let game = mid_game();
+    c.bench_function("clone", |b| b.iter(|| black_box(&game).clone()));
+    c.bench_function("lightweight clone", |b| b.iter(|| black_box(&game).lightweight_clone()));
+    c.bench_function("actions", |b| b.iter(|| black_box(&game).actions()));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -3,7 +3,49 @@ use bg_ai::{State, Outcome};
 use bg_ai::ismcts::Determinable;
 use rand::prelude::SliceRandom;
 use rand::Rng;
-use crate::{Acquire, Action, Phase, PlayerId};
+use rand_chacha::ChaCha8Rng;
+use crate::{Acquire, AcquireError, Action, Agent, PlayerId};
+
+/// A fast and deterministic opponent which looks one action ahead, taking whichever leaves the
+/// acting player furthest ahead of their best placed opponent. Players are valued at their net
+/// worth plus the bonuses they'd be paid if the game ended there, so it goes after majorities
+/// it's close to and ends the game when it's winning.
+pub struct HeuristicAgent;
+
+impl HeuristicAgent {
+    fn score(state: &Acquire, player_id: PlayerId) -> i64 {
+        let bonuses = if state.is_terminated() { Default::default() } else { state.projected_final_bonuses() };
+        let value = |player_id: PlayerId| {
+            state.player_liquidation_value(player_id) as i64 + bonuses.get(&player_id).copied().unwrap_or(0) as i64
+        };
+
+        let best_opponent = state.players
+            .iter()
+            .filter(|player| player.id != player_id)
+            .map(|player| value(player.id))
+            .max()
+            .unwrap_or(0);
+
+        value(player_id) - best_opponent
+    }
+}
+
+impl Agent for HeuristicAgent {
+    fn select_action(&mut self, _: &mut ChaCha8Rng, state: &Acquire, actions: &[Action]) -> Action {
+        let player_id = state.active_player();
+
+        // ties go to the earliest action, so it always makes the same choice
+        let mut best: Option<(i64, &Action)> = None;
+        for action in actions {
+            let score = Self::score(&state.apply_action(action.clone()), player_id);
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, action));
+            }
+        }
+
+        best.expect("an action").1.clone()
+    }
+}
 
 impl Determinable<Acquire, Action, PlayerId> for Acquire {
     fn determine<R: Rng>(&self, rng: &mut R, perspective_player: PlayerId) -> Acquire {
@@ -46,13 +88,14 @@ impl Determinable<Acquire, Action, PlayerId> for Acquire {
 }
 
 impl State<Action, PlayerId> for Acquire {
-    type Error = ();
+    type Error = AcquireError;
 
     fn actions(&self) -> Vec<Action> {
         self.actions()
     }
 
     fn apply_action<R: Rng + Sized>(&self, _: &mut R, action: &Action) -> Result<Self, Self::Error> where Self: Sized {
+        // the search only applies actions it got from `actions`, so there's no need to check them
         Ok(self.apply_action(action.clone()))
     }
 
@@ -72,13 +115,37 @@ impl State<Action, PlayerId> for Acquire {
     }
 
     fn current_player(&self) -> PlayerId {
-        match self.phase {
-            Phase::Merge { merging_player_id, .. } => merging_player_id,
-            _ => self.current_player_id,
-        }
+        self.active_player()
     }
 }
 
 impl bg_ai::Player for PlayerId {}
 
 impl bg_ai::Action for Action {}
+
+#[cfg(test)]
+mod test {
+    use crate::{Agent, HeuristicAgent, Options, RandomAgent, run_match};
+
+    #[test]
+    fn test_heuristic_beats_random() {
+        let num_games = 40;
+        let mut num_wins = 0;
+
+        for seed in 0..num_games {
+            // move the heuristic agent around the table so it doesn't always go first
+            let seat = seed as usize % 4;
+            let mut agents: Vec<Box<dyn Agent>> = (0..4)
+                .map(|n| if n == seat { Box::new(HeuristicAgent) as Box<dyn Agent> } else { Box::new(RandomAgent) })
+                .collect();
+
+            let result = run_match(&Options::default(), &mut agents, seed);
+            if result.winners.iter().any(|winner| winner.0 as usize == seat) {
+                num_wins += 1;
+            }
+        }
+
+        // a random agent would win about 10 of these give or take 3, so half is well clear of luck
+        assert!(num_wins >= num_games / 2, "only won {num_wins} of {num_games}");
+    }
+}
@@ -1,6 +1,8 @@
+use std::fmt::{Display, Formatter};
 use std::ops::Index;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Chain {
     Tower,
     Luxor,
@@ -11,7 +13,7 @@ pub enum Chain {
     Imperial,
 }
 
-const NUM_CHAINS: u8 = 7;
+pub const NUM_CHAINS: u8 = 7;
 pub const CHAIN_ARRAY: [Chain; NUM_CHAINS as usize] = [
     Chain::Tower,
     Chain::Luxor,
@@ -35,6 +37,36 @@ impl Chain {
         }
     }
 
+    /// The chain with the given initial, in either case.
+    pub fn from_initial(initial: char) -> Option<Chain> {
+        CHAIN_ARRAY.iter().find(|chain| chain.initial() == initial.to_ascii_uppercase()).copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Chain::Tower => "Tower",
+            Chain::Luxor => "Luxor",
+            Chain::American => "American",
+            Chain::Worldwide => "Worldwide",
+            Chain::Festival => "Festival",
+            Chain::Continental => "Continental",
+            Chain::Imperial => "Imperial",
+        }
+    }
+
+    /// The colour of the chain's tiles on the physical board, as rgb.
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Chain::Tower => (240, 200, 40),
+            Chain::Luxor => (200, 40, 40),
+            Chain::American => (40, 80, 200),
+            Chain::Worldwide => (140, 90, 50),
+            Chain::Festival => (40, 160, 60),
+            Chain::Continental => (40, 170, 190),
+            Chain::Imperial => (230, 110, 160),
+        }
+    }
+
     pub fn as_index(&self) -> usize {
         *self as usize
     }
@@ -44,7 +76,14 @@ impl Chain {
     }
 }
 
-#[derive(Clone)]
+impl Display for Chain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChainTable<T: Copy>(pub [T; NUM_CHAINS as usize]);
 
 impl<T: Copy> Index<&Chain> for ChainTable<T> {
@@ -78,14 +117,33 @@ impl<T: Copy + Default> Default for ChainTable<T> {
 
 #[cfg(test)]
 mod test {
-    
-    
-    
-    
-    
+    use itertools::Itertools;
+    use crate::chain::{Chain, CHAIN_ARRAY};
 
     #[test]
     fn test_chain_table() {
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Chain::Tower.name(), "Tower");
+        assert_eq!(Chain::Luxor.name(), "Luxor");
+        assert_eq!(Chain::American.name(), "American");
+        assert_eq!(Chain::Worldwide.name(), "Worldwide");
+        assert_eq!(Chain::Festival.name(), "Festival");
+        assert_eq!(Chain::Continental.name(), "Continental");
+        assert_eq!(Chain::Imperial.name(), "Imperial");
+
+        assert_eq!(Chain::Continental.to_string(), "Continental");
+
+        assert_eq!(Chain::from_initial('c'), Some(Chain::Continental));
+        assert_eq!(Chain::from_initial('T'), Some(Chain::Tower));
+        assert_eq!(Chain::from_initial('x'), None);
+    }
+
+    #[test]
+    fn test_colors_are_distinct() {
+        assert_eq!(CHAIN_ARRAY.iter().map(|chain| chain.color()).unique().count(), CHAIN_ARRAY.len());
+    }
+}
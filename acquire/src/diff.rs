@@ -0,0 +1,111 @@
+use crate::{Acquire, Action, Phase, PlayerId};
+use crate::chain::{Chain, CHAIN_ARRAY};
+use crate::grid::{Point, Slot};
+use crate::tile::Tile;
+
+/// What changed about one player between two states. Hands are left out since they're hidden.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerDiff {
+    pub player_id: PlayerId,
+    /// `(before, after)`, when it changed
+    pub money: Option<(u32, u32)>,
+    /// `(chain, before, after)` for every chain whose holding changed
+    pub stocks: Vec<(Chain, u8, u8)>,
+}
+
+/// The visible differences between two states, see `Acquire::apply_action_diff`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateDiff {
+    /// only the players with changes, in seat order
+    pub players: Vec<PlayerDiff>,
+    /// `(chain, before, after)` for the bank's stock
+    pub bank: Vec<(Chain, u8, u8)>,
+    /// `(point, before, after)`, row by row
+    pub cells: Vec<(Point, Slot, Slot)>,
+    /// `(before, after)`, when the phase changed
+    pub phase: Option<(Phase, Phase)>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == StateDiff::default()
+    }
+}
+
+impl Acquire {
+    /// Applies the action like `apply_action`, also reporting what it changed.
+    pub fn apply_action_diff(&self, action: Action) -> (Acquire, StateDiff) {
+        let next = self.apply_action(action);
+        let diff = self.diff(&next);
+
+        (next, diff)
+    }
+
+    /// Everything visible which differs between this state and `other`, assumed to be the same
+    /// game at a later point.
+    pub fn diff(&self, other: &Acquire) -> StateDiff {
+        let players = self.players.iter().zip(&other.players).filter_map(|(before, after)| {
+            let money = (before.money != after.money).then_some((before.money, after.money));
+            let stocks = changed_stocks(|chain| before.stocks.amount(chain), |chain| after.stocks.amount(chain));
+
+            if money.is_none() && stocks.is_empty() {
+                None
+            } else {
+                Some(PlayerDiff { player_id: before.id, money, stocks })
+            }
+        }).collect();
+
+        let cells = Tile::all_for_board(self.grid.width, self.grid.height)
+            .map(|tile| (tile.0, self.grid.get(tile.0), other.grid.get(tile.0)))
+            .filter(|(_, before, after)| before != after)
+            .collect();
+
+        StateDiff {
+            players,
+            bank: changed_stocks(|chain| self.stocks.amount(chain), |chain| other.stocks.amount(chain)),
+            cells,
+            phase: (self.phase != other.phase).then(|| (self.phase.clone(), other.phase.clone())),
+        }
+    }
+}
+
+fn changed_stocks(before: impl Fn(Chain) -> u8, after: impl Fn(Chain) -> u8) -> Vec<(Chain, u8, u8)> {
+    CHAIN_ARRAY
+        .iter()
+        .map(|chain| (*chain, before(*chain), after(*chain)))
+        .filter(|(_, before, after)| before != after)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Acquire, Action, Options, Phase, PlayerId, tile};
+    use crate::chain::Chain;
+    use crate::grid::Slot;
+
+    #[test]
+    fn test_chain_creation_diff() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        game.grid.place(tile!("A1"));
+        game.players[0].tiles[0] = tile!("A2");
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A2")));
+
+        let (next, diff) = game.apply_action_diff(Action::SelectChainToCreate(PlayerId(0), Chain::Tower));
+        assert_eq!(next.to_bytes(), game.apply_action(Action::SelectChainToCreate(PlayerId(0), Chain::Tower)).to_bytes());
+
+        // the founder's free share comes out of the bank
+        assert_eq!(diff.players.len(), 1);
+        assert_eq!(diff.players[0].player_id, PlayerId(0));
+        assert_eq!(diff.players[0].money, None);
+        assert_eq!(diff.players[0].stocks, vec![(Chain::Tower, 0, 1)]);
+        assert_eq!(diff.bank, vec![(Chain::Tower, 25, 24)]);
+
+        assert_eq!(diff.cells, vec![
+            (tile!("A1"), Slot::NoChain, Slot::Chain(Chain::Tower)),
+            (tile!("A2"), Slot::NoChain, Slot::Chain(Chain::Tower)),
+        ]);
+        assert_eq!(diff.phase, Some((Phase::AwaitingChainCreationSelection, Phase::AwaitingStockPurchase)));
+
+        assert!(game.diff(&game).is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+use thiserror::Error;
+use crate::{Acquire, Action, BuyOption, MergePhase, Phase, PlayerId};
+use crate::chain::{Chain, CHAIN_ARRAY};
+use crate::grid::Slot;
+use crate::money::chain_value;
+use crate::tile::Tile;
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum AcquireError {
+    #[error("the game is over")]
+    GameOver,
+    #[error("{action} can't be taken while {phase:?}")]
+    WrongPhase {
+        action: Action,
+        phase: Phase,
+    },
+    #[error("it's player {}'s decision, not player {}'s", expected.0, actual.0)]
+    WrongPlayer {
+        expected: PlayerId,
+        actual: PlayerId,
+    },
+    #[error("{0} is not on the board")]
+    OutOfBounds(Tile),
+    #[error("{0} is not in the player's hand")]
+    TileNotInHand(Tile),
+    #[error("the player can't afford {0}")]
+    InsufficientFunds(Action),
+    #[error("there isn't enough stock for {0}")]
+    InsufficientStock(Action),
+    #[error("{0} is not a legal action")]
+    IllegalAction(Action),
+    #[error("no chain is being created while {0:?}")]
+    NotCreatingChain(Phase),
+    #[error("there would be {actual} shares of {chain} in play, more than the {cap} issued")]
+    StockCapExceeded {
+        chain: Chain,
+        cap: u16,
+        actual: u16,
+    },
+}
+
+impl Acquire {
+    /// Like `apply_action`, but checks the action is legal first instead of panicking part way
+    /// through applying it. With `Options::enforce_stock_cap` the resulting state is checked too.
+    pub fn try_apply_action(&self, action: Action) -> Result<Acquire, AcquireError> {
+        self.check_action(&action)?;
+
+        let game = self.apply_action(action);
+        if game.options.enforce_stock_cap {
+            game.check_stock_cap()?;
+        }
+
+        Ok(game)
+    }
+
+    /// The shares of a chain held by the bank and every player combined.
+    pub fn shares_in_play(&self, chain: Chain) -> u16 {
+        self.stocks.amount(chain) as u16 +
+            self.players.iter().map(|player| player.stocks.amount(chain) as u16).sum::<u16>()
+    }
+
+    /// Errors on the first chain with more shares in play than the bank was given to start with.
+    pub fn check_stock_cap(&self) -> Result<(), AcquireError> {
+        for chain in CHAIN_ARRAY {
+            let cap = self.options.num_stock.amount(chain) as u16;
+            let actual = self.shares_in_play(chain);
+
+            if actual > cap {
+                return Err(AcquireError::StockCapExceeded { chain, cap, actual });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies each action in turn with `try_apply_action`, stopping at the first illegal one
+    /// and returning its index along with why it couldn't be applied.
+    pub fn apply_actions(&self, actions: &[Action]) -> Result<Acquire, (usize, AcquireError)> {
+        let mut game = self.clone();
+        for (idx, action) in actions.iter().enumerate() {
+            game = game.try_apply_action(action.clone()).map_err(|error| (idx, error))?;
+        }
+
+        Ok(game)
+    }
+
+    pub(crate) fn check_action(&self, action: &Action) -> Result<(), AcquireError> {
+        if self.terminated {
+            return Err(AcquireError::GameOver);
+        }
+
+        let phase_matches = matches!(
+            (action, &self.phase),
+            (Action::PlaceTile(..), Phase::AwaitingTilePlacement) |
+            (Action::SelectChainToCreate(..), Phase::AwaitingChainCreationSelection) |
+            (Action::PurchaseStock(..), Phase::AwaitingStockPurchase) |
+            (Action::Terminate(..), Phase::AwaitingGameTerminationDecision) |
+            (Action::SelectChainForTiebreak(..), Phase::Merge { phase: MergePhase::AwaitingTiebreakSelection { .. }, .. }) |
+            (Action::DecideMerge { .. }, Phase::Merge { phase: MergePhase::AwaitingMergeDecision, .. })
+        );
+
+        if !phase_matches {
+            return Err(AcquireError::WrongPhase { action: action.clone(), phase: self.phase.clone() });
+        }
+
+        let expected = self.active_player();
+        if action.player_id() != expected {
+            return Err(AcquireError::WrongPlayer { expected, actual: action.player_id() });
+        }
+
+        let player = self.get_player_by_id(expected);
+
+        match action {
+            Action::PlaceTile(_, tile) => {
+                if self.grid.is_pt_out_of_bounds(tile.0) {
+                    return Err(AcquireError::OutOfBounds(*tile));
+                }
+
+                if !player.tiles.contains(tile) {
+                    return Err(AcquireError::TileNotInHand(*tile));
+                }
+
+                if !matches!(self.grid.get(tile.0), Slot::Empty(_)) {
+                    return Err(AcquireError::IllegalAction(action.clone()));
+                }
+            }
+
+            Action::PurchaseStock(_, buys) => {
+                let num_buys = buys.iter().filter(|buy| matches!(buy, BuyOption::Chain(_))).count();
+                if num_buys > self.options.max_buys_per_turn as usize {
+                    return Err(AcquireError::IllegalAction(action.clone()));
+                }
+
+                let mut money = player.money;
+                let mut stocks = self.stocks.clone();
+
+                for buy in buys {
+                    if let BuyOption::Chain(chain) = buy {
+                        if self.grid.chain_size(*chain) == 0 {
+                            return Err(AcquireError::IllegalAction(action.clone()));
+                        }
+
+                        if stocks.withdraw(*chain, 1).is_err() {
+                            return Err(AcquireError::InsufficientStock(action.clone()));
+                        }
+
+                        let price = chain_value(*chain, self.grid.chain_size(*chain));
+                        if price > money {
+                            return Err(AcquireError::InsufficientFunds(action.clone()));
+                        }
+
+                        money -= price;
+                    }
+                }
+
+                // that's everything, `actions` only lists one ordering of each purchase but any
+                // order of the same buys is just as legal
+                return Ok(());
+            }
+
+            Action::DecideMerge { decision, .. } => {
+                let chains = decision.merging_chains;
+                let held = player.stocks.amount(chains.defunct_chain);
+
+                if decision.sell as u16 + decision.trade_in as u16 > held as u16 ||
+                    decision.trade_in / 2 > self.stocks.amount(chains.merging_chain) {
+                    return Err(AcquireError::InsufficientStock(action.clone()));
+                }
+            }
+
+            Action::SelectChainToCreate(..) |
+            Action::SelectChainForTiebreak(..) |
+            Action::Terminate(..) => {}
+        }
+
+        // everything more specific has been ruled out
+        if !self.actions().contains(action) {
+            return Err(AcquireError::IllegalAction(action.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Acquire, AcquireError, Action, BuyOption, Chain, Options, Phase, PlayerId, Tile, tile};
+
+    fn game_test_instance() -> Acquire {
+        Acquire::from_seed(2, &Options::default())
+    }
+
+    #[test]
+    fn test_legal_action() {
+        let game = game_test_instance();
+        let action = game.actions().remove(0);
+
+        assert_eq!(game.try_apply_action(action.clone()).unwrap().to_bytes(), game.apply_action(action).to_bytes());
+    }
+
+    #[test]
+    fn test_apply_actions() {
+        let mut game = game_test_instance();
+        let mut actions = vec![];
+        for _ in 0..6 {
+            let action = game.actions().remove(0);
+            game = game.apply_action(action.clone());
+            actions.push(action);
+        }
+
+        let start = game_test_instance();
+        assert_eq!(start.apply_actions(&actions).unwrap().to_bytes(), game.to_bytes());
+        assert_eq!(start.apply_actions(&[]).unwrap().to_bytes(), start.to_bytes());
+
+        // the first action again, by which point its tile has already been placed
+        actions.push(actions[0].clone());
+        actions.push(game.actions().remove(0));
+        let (idx, error) = start.apply_actions(&actions).err().unwrap();
+        assert_eq!(idx, 6);
+        assert_eq!(error, game.try_apply_action(actions[0].clone()).err().unwrap());
+    }
+
+    #[test]
+    fn test_tile_errors() {
+        let game = game_test_instance();
+        let in_hand = game.players[0].tiles[0];
+        let not_in_hand = game.players[1].tiles[0];
+
+        assert_eq!(
+            game.try_apply_action(Action::PlaceTile(PlayerId(0), Tile::new(12, 0))).err(),
+            Some(AcquireError::OutOfBounds(Tile::new(12, 0)))
+        );
+        assert_eq!(
+            game.try_apply_action(Action::PlaceTile(PlayerId(0), not_in_hand)).err(),
+            Some(AcquireError::TileNotInHand(not_in_hand))
+        );
+        assert_eq!(
+            game.try_apply_action(Action::PlaceTile(PlayerId(1), in_hand)).err(),
+            Some(AcquireError::WrongPlayer { expected: PlayerId(0), actual: PlayerId(1) })
+        );
+        assert!(matches!(
+            game.try_apply_action(Action::Terminate(PlayerId(0), true)),
+            Err(AcquireError::WrongPhase { phase: Phase::AwaitingTilePlacement, .. })
+        ));
+    }
+
+    #[test]
+    fn test_purchase_errors() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.phase = Phase::AwaitingStockPurchase;
+
+        let buy = |chains: [BuyOption; 3]| Action::PurchaseStock(PlayerId(0), chains.to_vec());
+        let tower = BuyOption::Chain(Chain::Tower);
+
+        // tower is $200 a share
+        game.players[0].money = 500;
+        let action = buy([tower, tower, tower]);
+        assert_eq!(game.try_apply_action(action.clone()).err(), Some(AcquireError::InsufficientFunds(action)));
+
+        game.players[0].money = 6000;
+        game.stocks.withdraw(Chain::Tower, 23).unwrap();
+        let action = buy([tower, tower, tower]);
+        assert_eq!(game.try_apply_action(action.clone()).err(), Some(AcquireError::InsufficientStock(action)));
+
+        // luxor isn't on the board
+        let action = buy([BuyOption::Chain(Chain::Luxor), BuyOption::None, BuyOption::None]);
+        assert_eq!(game.try_apply_action(action.clone()).err(), Some(AcquireError::IllegalAction(action)));
+
+        assert!(game.try_apply_action(buy([tower, tower, BuyOption::None])).is_ok());
+    }
+
+    #[test]
+    fn test_purchase_order() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Imperial);
+        game.phase = Phase::AwaitingStockPurchase;
+
+        let tower = BuyOption::Chain(Chain::Tower);
+        let imperial = BuyOption::Chain(Chain::Imperial);
+        let listed = Action::PurchaseStock(PlayerId(0), vec![tower, imperial, BuyOption::None]);
+        assert!(game.actions().contains(&listed));
+
+        // the same shares bought in any other order end up in the same place
+        let expected = game.apply_action(listed).to_bytes();
+        for buys in [
+            vec![BuyOption::None, imperial, tower],
+            vec![imperial, BuyOption::None, tower],
+            vec![imperial, tower],
+        ] {
+            let reordered = game.try_apply_action(Action::PurchaseStock(PlayerId(0), buys)).unwrap();
+            assert_eq!(reordered.to_bytes(), expected);
+        }
+
+        let too_many = Action::PurchaseStock(PlayerId(0), vec![tower, tower, imperial, imperial]);
+        assert_eq!(game.try_apply_action(too_many.clone()).err(), Some(AcquireError::IllegalAction(too_many)));
+    }
+
+    #[test]
+    fn test_stock_cap() {
+        let mut game = game_test_instance();
+        game.options.enforce_stock_cap = true;
+
+        for x in 1..=4 {
+            game.grid.place(Tile::new(x - 1, 0));
+        }
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("A6"));
+        game.grid.place(tile!("A7"));
+        game.grid.fill_chain(tile!("A6"), Chain::Luxor);
+
+        game.stocks.withdraw(Chain::Luxor, 2).unwrap();
+        game.players[1].stocks.deposit(Chain::Luxor, 2);
+
+        game.players[0].tiles[0] = tile!("A5");
+        let game = game.try_apply_action(Action::PlaceTile(PlayerId(0), tile!("A5"))).unwrap();
+
+        let trade_in = game.actions().into_iter().find(|action| {
+            matches!(action, Action::DecideMerge { decision, .. } if decision.trade_in == 2)
+        }).unwrap();
+
+        // trading in moves shares between the bank and the player, so nothing is created
+        let traded = game.try_apply_action(trade_in.clone()).unwrap();
+        assert_eq!(traded.shares_in_play(Chain::American), 25);
+        assert_eq!(traded.shares_in_play(Chain::Luxor), 25);
+
+        // a share that never came out of the bank is caught by the trade that touches it
+        let mut miscounted = game.clone();
+        miscounted.players[0].stocks.deposit(Chain::American, 1);
+        assert_eq!(
+            miscounted.try_apply_action(trade_in.clone()).err(),
+            Some(AcquireError::StockCapExceeded { chain: Chain::American, cap: 25, actual: 26 })
+        );
+
+        // without enforcement it goes through all the same
+        miscounted.options.enforce_stock_cap = false;
+        assert!(miscounted.try_apply_action(trade_in).is_ok());
+    }
+
+    #[test]
+    fn test_game_over() {
+        let mut game = game_test_instance();
+        game.terminated = true;
+
+        let action = game.players[0].tiles[0];
+        assert_eq!(game.try_apply_action(Action::PlaceTile(PlayerId(0), action)).err(), Some(AcquireError::GameOver));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::grid::{Direction, Point};
+
+/// The four orthogonal neighbours of `pt` in `Direction::NEIGHBOUR_ORDER`, some of which may be
+/// off the board.
+pub fn adjacent_points(pt: Point) -> [Point; 4] {
+    Direction::NEIGHBOUR_ORDER.map(|dir| dir.from(pt))
+}
+
+/// Whether `pt` lies on a board of the given size.
+pub fn in_bounds(pt: Point, width: u8, height: u8) -> bool {
+    pt.x >= 0 &&
+        pt.y >= 0 &&
+        (pt.x as i16) < width as i16 &&
+        (pt.y as i16) < height as i16
+}
+
+/// How many orthogonal steps it takes to get from one point to the other.
+pub fn manhattan(a: Point, b: Point) -> u16 {
+    (a.x as i16 - b.x as i16).unsigned_abs() + (a.y as i16 - b.y as i16).unsigned_abs()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::{adjacent_points, in_bounds, manhattan};
+    use crate::grid::Point;
+
+    #[test]
+    fn test_adjacent_points() {
+        let center = Point { x: 5, y: 4 };
+        assert_eq!(adjacent_points(center), [
+            Point { x: 5, y: 5 },
+            Point { x: 6, y: 4 },
+            Point { x: 5, y: 3 },
+            Point { x: 4, y: 4 },
+        ]);
+
+        // the corner's neighbours still come back, two of them off the board
+        let corner = Point { x: 0, y: 0 };
+        let on_board: Vec<Point> = adjacent_points(corner).into_iter().filter(|pt| in_bounds(*pt, 12, 9)).collect();
+        assert_eq!(on_board, vec![Point { x: 0, y: 1 }, Point { x: 1, y: 0 }]);
+
+        let edge = Point { x: 11, y: 4 };
+        let on_board = adjacent_points(edge).into_iter().filter(|pt| in_bounds(*pt, 12, 9)).count();
+        assert_eq!(on_board, 3);
+    }
+
+    #[test]
+    fn test_in_bounds() {
+        assert!(in_bounds(Point { x: 0, y: 0 }, 12, 9));
+        assert!(in_bounds(Point { x: 11, y: 8 }, 12, 9));
+        assert!(in_bounds(Point { x: 5, y: 4 }, 12, 9));
+
+        assert!(!in_bounds(Point { x: 12, y: 0 }, 12, 9));
+        assert!(!in_bounds(Point { x: 0, y: 9 }, 12, 9));
+        assert!(!in_bounds(Point { x: -1, y: 4 }, 12, 9));
+        assert!(!in_bounds(Point { x: 5, y: -1 }, 12, 9));
+        assert!(!in_bounds(Point { x: 0, y: 0 }, 0, 0));
+    }
+
+    #[test]
+    fn test_manhattan() {
+        let corner = Point { x: 0, y: 0 };
+        let center = Point { x: 5, y: 4 };
+
+        assert_eq!(manhattan(center, center), 0);
+        assert_eq!(manhattan(corner, center), 9);
+        assert_eq!(manhattan(center, corner), 9);
+        assert_eq!(manhattan(corner, Point { x: 11, y: 8 }), 19);
+        assert_eq!(manhattan(Point { x: 11, y: 4 }, Point { x: 11, y: 0 }), 4);
+    }
+}
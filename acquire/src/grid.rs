@@ -1,13 +1,16 @@
+use std::cmp::{Ordering, Reverse};
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
+use std::ops::Deref;
 use itertools::Itertools;
-use crate::MergingChains;
-use crate::tile::{Tile, TileParseError};
+use crate::{geometry, MergingChains};
+use crate::tile::{map_i8_to_letter, CoordinateStyle, Tile, TileParseError};
 use ahash::{HashMap, HashSet};
-use crate::chain::{Chain, ChainTable};
+use thiserror::Error;
+use crate::chain::{Chain, ChainTable, CHAIN_ARRAY, NUM_CHAINS};
 
-const SAFE_CHAIN_SIZE: u16 = 11;
-const GAME_ENDING_CHAIN_SIZE: u16 = 41;
+pub const DEFAULT_SAFE_CHAIN_SIZE: u16 = 11;
+pub const DEFAULT_GAME_ENDING_CHAIN_SIZE: u16 = 41;
 
 #[derive(Clone)]
 pub struct Grid {
@@ -16,6 +19,46 @@ pub struct Grid {
     pub data: HashMap<Point, Slot>,
     chain_sizes: ChainTable<u16>,
     pub previously_placed_tile_pt: Option<Point>,
+    pub safe_chain_size: u16,
+    pub game_ending_chain_size: u16,
+    /// only the first `num_chains` of `CHAIN_ARRAY` can be founded
+    pub num_chains: u8,
+    /// points which frontends want drawn distinctly, cleared at the start of every turn
+    pub indicators: HashSet<Point>,
+}
+
+/// Aggregate counts over the board, see `Grid::board_stats`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoardStats {
+    pub num_chains: u8,
+    pub chain_sizes: ChainTable<u16>,
+    pub num_safe_chains: u8,
+    /// placed tiles which don't belong to a chain yet
+    pub num_nochain_tiles: u16,
+    pub num_legal: u16,
+    pub num_temporarily_illegal: u16,
+    pub num_permanently_illegal: u16,
+}
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum GridError {
+    #[error("{0} is not on the board")]
+    OutOfBounds(Tile),
+}
+
+/// Every slot on the board, row by row, see `Grid::snapshot`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridSnapshot {
+    pub width: u8,
+    pub height: u8,
+    pub slots: Vec<Slot>,
+}
+
+impl GridSnapshot {
+    pub fn get(&self, pt: Point) -> Slot {
+        self.slots[pt.y as usize * self.width as usize + pt.x as usize]
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -41,26 +84,32 @@ impl Grid {
             data: Default::default(),
             chain_sizes: Default::default(),
             previously_placed_tile_pt: None,
+            safe_chain_size: DEFAULT_SAFE_CHAIN_SIZE,
+            game_ending_chain_size: DEFAULT_GAME_ENDING_CHAIN_SIZE,
+            num_chains: NUM_CHAINS,
+            indicators: Default::default(),
         }
     }
 
+    /// The chains which can be founded in this game.
+    pub fn active_chains(&self) -> &'static [Chain] {
+        &CHAIN_ARRAY[..self.num_chains as usize]
+    }
+
     pub fn all_chains_are_safe(&self) -> bool {
-        self.chain_sizes.0.iter().all(|size| *size >= SAFE_CHAIN_SIZE)
+        self.active_chains().iter().all(|chain| self.chain_size(*chain) >= self.safe_chain_size)
     }
 
     fn num_safe_chains(&self) -> usize {
-        self.chain_sizes.0.iter().filter(|size| **size >= SAFE_CHAIN_SIZE).count()
+        self.chain_sizes.0.iter().filter(|size| **size >= self.safe_chain_size).count()
     }
 
     pub fn game_ending_chain_exists(&self) -> bool {
-        self.chain_sizes.0.iter().any(|size| *size >= GAME_ENDING_CHAIN_SIZE)
+        self.chain_sizes.0.iter().any(|size| *size >= self.game_ending_chain_size)
     }
 
     pub fn is_pt_out_of_bounds(&self, pt: Point) -> bool {
-        pt.x < 0 ||
-            pt.y < 0 ||
-            pt.x > self.width as i8 ||
-            pt.y > self.height as i8
+        !geometry::in_bounds(pt, self.width, self.height)
     }
 
     pub fn get(&self, pt: Point) -> Slot {
@@ -72,11 +121,20 @@ impl Grid {
     }
 
 
+    /// Like `try_place`, but panics if the tile is off the board.
     pub fn place(&mut self, tile: Tile) -> PlaceTileResult {
+        self.try_place(tile).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_place(&mut self, tile: Tile) -> Result<PlaceTileResult, GridError> {
         if self.is_pt_out_of_bounds(tile.0) {
-            panic!("setting invalid pt {:?}", tile.0);
+            return Err(GridError::OutOfBounds(tile));
         }
 
+        Ok(self.place_on_board(tile))
+    }
+
+    fn place_on_board(&mut self, tile: Tile) -> PlaceTileResult {
         let neighbours = self.neighbours(tile.0);
         let neighbouring_chains = self.chains_in_slots(&neighbours);
         let num_neighbouring_chains = neighbouring_chains.len();
@@ -113,10 +171,9 @@ impl Grid {
 
                 let largest_chain = largest_chains[0];
 
-                // sort non-largest chains into a list in descending chain size order - ties in defunct chains don't matter as far as I know
-                // nor do I comprehend any advantage to sorting them in this way, it's just in the rules.
-                let mut other_chains: Vec<Chain> = neighbouring_chains.into_iter().filter(|chain| *chain != largest_chain).collect();
-                other_chains.sort_by_key(|chain| self.chain_sizes.get(chain));
+                // the rules deal with the defunct chains from largest to smallest
+                let mut other_chains: Vec<Chain> = neighbouring_chains.iter().copied().filter(|chain| *chain != largest_chain).collect();
+                self.sort_defunct_chains(&mut other_chains);
 
                 let merger_list = other_chains
                     .iter()
@@ -161,17 +218,31 @@ impl Grid {
 
             1 => {
                 let chain = neighbouring_chains[0];
+                let was_safe = self.chain_size(chain) >= self.safe_chain_size;
+
                 self.set_slot(tile.0, Slot::Chain(chain));
 
                 self.update_legality_of_neighbours(tile.0);
                 self.update_chain_of_neighbours(tile.0, chain);
 
+                // growing into a safe chain can lock the gaps between it and other safe chains,
+                // refilling re-evaluates the legality all the way around the chain
+                if !was_safe && self.chain_size(chain) >= self.safe_chain_size {
+                    self.fill_chain(tile.0, chain);
+                }
+
                 self.previously_placed_tile_pt = Some(tile.0);
                 PlaceTileResult::Proceed
             }
         }
     }
 
+    /// Puts defunct chains in the order they're dealt with in a merge, largest first. The rules
+    /// don't say how to order defunct chains of the same size, so those go in `Chain` order.
+    pub(crate) fn sort_defunct_chains(&self, chains: &mut [Chain]) {
+        chains.sort_by_key(|chain| (Reverse(self.chain_size(*chain)), *chain));
+    }
+
     fn update_chain_of_neighbours(&mut self, pt: Point, chain: Chain) {
         for neighbouring_pt in self.neighbouring_points(pt) {
             match self.get(neighbouring_pt) {
@@ -233,16 +304,18 @@ impl Grid {
         }
     }
 
-    /// Collects a vec of existing hotel chains in the slice of slots
-    pub fn chains_in_slots(&self, slots: &[Slot]) -> Vec<Chain> {
-        slots.iter().filter_map(|slot| {
-            match slot {
-                Slot::Empty(_) |
-                Slot::Limbo |
-                Slot::NoChain => None,
-                Slot::Chain(chain) => Some(*chain),
+    /// Collects the distinct hotel chains in the slots, in the order they're first seen.
+    /// This runs for every candidate tile, so it avoids allocating.
+    pub fn chains_in_slots(&self, slots: &[Slot; 4]) -> NeighbouringChains {
+        let mut chains = NeighbouringChains::default();
+
+        for slot in slots {
+            if let Slot::Chain(chain) = slot {
+                chains.insert(*chain);
             }
-        }).unique().collect()
+        }
+
+        chains
     }
 
     pub fn num_nochains_chains_in_slots(&self, slots: &[Slot]) -> u8 {
@@ -258,26 +331,20 @@ impl Grid {
         })
     }
 
-    /// Returns a \[North,West,South,East\] array of points which are orthogonal neighbours to
-    /// the center point.
+    /// Returns a \[South,East,North,West\] array of points which are orthogonal neighbours to
+    /// the center point, see `Direction::NEIGHBOUR_ORDER`.
     pub fn neighbouring_points(&self, pt: Point) -> [Point; 4] {
-        [
-            Point { x: pt.x, y: pt.y + 1 },
-            Point { x: pt.x + 1, y: pt.y },
-            Point { x: pt.x, y: pt.y - 1 },
-            Point { x: pt.x - 1, y: pt.y },
-        ]
+        geometry::adjacent_points(pt)
     }
 
-    /// Returns a \[North,West,South,East\] array of grid slots which are orthogonal neighbours to
-    /// the center point.
+    /// Returns a \[South,East,North,West\] array of grid slots which are orthogonal neighbours to
+    /// the center point, see `Direction::NEIGHBOUR_ORDER`.
     pub fn neighbours(&self, pt: Point) -> [Slot; 4] {
-        [
-            self.get(Point { x: pt.x, y: pt.y + 1 }),
-            self.get(Point { x: pt.x + 1, y: pt.y }),
-            self.get(Point { x: pt.x, y: pt.y - 1 }),
-            self.get(Point { x: pt.x - 1, y: pt.y }),
-        ]
+        Direction::NEIGHBOUR_ORDER.map(|dir| self.get(dir.from(pt)))
+    }
+
+    pub fn neighbour(&self, pt: Point, dir: Direction) -> Slot {
+        self.get(dir.from(pt))
     }
 
     fn update_legality_of_all_nochains(&mut self) {
@@ -364,12 +431,16 @@ impl Grid {
                     stack.push_back(*valid_neighbour_pt);
                 }
             }
+        }
 
-            for pt in empty_surrounding_pts {
-                self.update_legality_of_slot(pt);
-            }
+        // tiles next to the filled area may have been next to a lone tile that has now been
+        // absorbed, or next to a chain which is now safe
+        for pt in empty_surrounding_pts {
+            self.update_legality_of_slot(pt);
         }
 
+        // whether another chain can be founded affects tiles next to any lone tile on the board,
+        // so this can't be limited to the area around the filled chain
         if prev_temporary_illegal_possible != self.temporary_illegal_possible() {
             self.update_legality_of_all_nochains();
         }
@@ -385,19 +456,17 @@ impl Grid {
     }
 
     pub fn available_chains(&self) -> Vec<Chain> {
-        self.chain_sizes.0
+        self.active_chains()
             .iter()
-            .enumerate()
-            .filter(|(_, size)| **size == 0)
-            .map(|(chain_idx, _)| Chain::from_index(chain_idx))
+            .filter(|chain| self.chain_size(**chain) == 0)
+            .copied()
             .collect()
     }
 
     pub fn num_available_chains(&self) -> usize {
-        self.chain_sizes.0
+        self.active_chains()
             .iter()
-            .enumerate()
-            .filter(|(_, size)| **size == 0)
+            .filter(|chain| self.chain_size(**chain) == 0)
             .count()
     }
 
@@ -405,6 +474,50 @@ impl Grid {
         self.chain_sizes.get(&chain)
     }
 
+    pub fn chain_sizes_snapshot(&self) -> ChainTable<u16> {
+        self.chain_sizes.clone()
+    }
+
+    /// The biggest chain on the board and its size, ties go to the chain which comes first in
+    /// `CHAIN_ARRAY`.
+    pub fn largest_chain(&self) -> Option<(Chain, u16)> {
+        self.chain_sizes.0
+            .iter()
+            .enumerate()
+            .filter(|(_, size)| **size > 0)
+            .fold(None, |largest: Option<(Chain, u16)>, (chain_idx, size)| {
+                match largest {
+                    Some((_, largest_size)) if largest_size >= *size => largest,
+                    _ => Some((Chain::from_index(chain_idx), *size)),
+                }
+            })
+    }
+
+    /// Recounts every chain's size from the slot data, used after the data has been replaced
+    /// wholesale rather than through `set_slot`.
+    pub(crate) fn recount_chain_sizes(&mut self) {
+        self.chain_sizes = self.counted_chain_sizes();
+    }
+
+    /// Checks the sizes tracked by `set_slot` against a fresh count of the board. Only built
+    /// with debug assertions, it's there to catch bookkeeping bugs in tests.
+    #[cfg(debug_assertions)]
+    pub fn verify_chain_sizes(&self) -> bool {
+        let counted = self.counted_chain_sizes();
+        CHAIN_ARRAY.iter().all(|chain| counted.get(chain) == self.chain_sizes.get(chain))
+    }
+
+    fn counted_chain_sizes(&self) -> ChainTable<u16> {
+        let mut chain_sizes: ChainTable<u16> = ChainTable::default();
+        for slot in self.data.values() {
+            if let Slot::Chain(chain) = slot {
+                chain_sizes.set(chain, chain_sizes.get(chain) + 1);
+            }
+        }
+
+        chain_sizes
+    }
+
     fn permanently_illegal_possible(&self) -> bool {
         self.num_safe_chains() > 1
     }
@@ -432,7 +545,7 @@ impl Grid {
                     return (false, false);
                 }
 
-                if neighbouring_chains.iter().filter(|chain| self.chain_size(**chain) >= SAFE_CHAIN_SIZE).count() > 1 {
+                if neighbouring_chains.iter().filter(|chain| self.chain_size(**chain) >= self.safe_chain_size).count() > 1 {
                     return (true, true);
                 }
             }
@@ -445,7 +558,7 @@ impl Grid {
                 let num_neighbouring_nochains = self.num_nochains_chains_in_slots(&neighbours);
                 if num_neighbouring_nochains > 0 {
 
-                    // illegal to form an 8th chain
+                    // illegal to form a chain when every chain is already on the board
                     // but also this specific form of illegal tile cannot be traded in
                     if self.num_available_chains() == 0 {
                         return (true, false);
@@ -457,39 +570,207 @@ impl Grid {
 
         (false, false)
     }
-}
 
+    /// The number of connected unclaimed tiles at `pt`, which is how big a chain founded there
+    /// would start out.
+    pub fn pending_chain_size(&self, pt: Point) -> u16 {
+        let mut stack: VecDeque<Point> = Default::default();
+        let mut visited: HashSet<Point> = Default::default();
+        let mut size = 0;
+
+        stack.push_back(pt);
+        visited.insert(pt);
+
+        while let Some(pt) = stack.pop_front() {
+            if !matches!(self.get(pt), Slot::NoChain | Slot::Limbo) {
+                continue;
+            }
+
+            size += 1;
+
+            for neighbour in self.neighbouring_points(pt) {
+                if visited.insert(neighbour) {
+                    stack.push_back(neighbour);
+                }
+            }
+        }
+
+        size
+    }
+
+    /// How big the chain containing `pt` would be if a tile were placed there, counting every
+    /// chain it would merge and every unclaimed tile it would pull in.
+    pub fn size_after_placing(&self, pt: Point) -> u16 {
+        let mut stack: VecDeque<Point> = Default::default();
+        let mut visited: HashSet<Point> = Default::default();
+        let mut size = 1;
+
+        for neighbour in self.neighbouring_points(pt) {
+            if visited.insert(neighbour) {
+                stack.push_back(neighbour);
+            }
+        }
+        visited.insert(pt);
+
+        while let Some(pt) = stack.pop_front() {
+            if matches!(self.get(pt), Slot::Empty(_)) {
+                continue;
+            }
+
+            size += 1;
+
+            for neighbour in self.neighbouring_points(pt) {
+                if visited.insert(neighbour) {
+                    stack.push_back(neighbour);
+                }
+            }
+        }
+
+        size
+    }
+
+    /// The empty cells the chain could grow into, sorted row by row.
+    pub fn chain_frontier(&self, chain: Chain) -> Vec<Point> {
+        self.data
+            .iter()
+            .filter(|(_, slot)| **slot == Slot::Chain(chain))
+            .flat_map(|(pt, _)| self.neighbouring_points(*pt))
+            .filter(|pt| !self.is_pt_out_of_bounds(*pt) && matches!(self.get(*pt), Slot::Empty(_)))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// A copy of the grid without the frontend-only `indicators`.
+    pub(crate) fn clone_without_indicators(&self) -> Grid {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.data.clone(),
+            chain_sizes: self.chain_sizes.clone(),
+            previously_placed_tile_pt: self.previously_placed_tile_pt,
+            safe_chain_size: self.safe_chain_size,
+            game_ending_chain_size: self.game_ending_chain_size,
+            num_chains: self.num_chains,
+            indicators: Default::default(),
+        }
+    }
+
+    pub fn board_stats(&self) -> BoardStats {
+        let mut stats = BoardStats {
+            num_chains: self.existing_chains().len() as u8,
+            chain_sizes: self.chain_sizes.clone(),
+            num_safe_chains: self.num_safe_chains() as u8,
+            num_nochain_tiles: 0,
+            num_legal: 0,
+            num_temporarily_illegal: 0,
+            num_permanently_illegal: 0,
+        };
+
+        for tile in Tile::all_for_board(self.width, self.height) {
+            match self.get(tile.0) {
+                Slot::Empty(Legality::Legal) => stats.num_legal += 1,
+                Slot::Empty(Legality::TemporarilyIllegal) => stats.num_temporarily_illegal += 1,
+                Slot::Empty(Legality::PermanentIllegal) => stats.num_permanently_illegal += 1,
+                Slot::NoChain => stats.num_nochain_tiles += 1,
+                Slot::Limbo |
+                Slot::Chain(_) => {}
+            }
+        }
+
+        stats
+    }
+
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            width: self.width,
+            height: self.height,
+            slots: Tile::all_for_board(self.width, self.height).map(|tile| self.get(tile.0)).collect(),
+        }
+    }
+
+    /// Replaces the board with the snapshot's. Settings like the safe chain size aren't part of
+    /// the snapshot and are left alone.
+    pub fn restore(&mut self, snapshot: &GridSnapshot) {
+        self.width = snapshot.width;
+        self.height = snapshot.height;
+        self.data = Tile::all_for_board(snapshot.width, snapshot.height)
+            .zip(&snapshot.slots)
+            .filter(|(_, slot)| **slot != Slot::Empty(Legality::Legal))
+            .map(|(tile, slot)| (tile.0, *slot))
+            .collect();
+        self.recount_chain_sizes();
+    }
+
+    /// Every empty cell on the board, row by row, with its current legality.
+    pub fn legality_map(&self) -> Vec<(Point, Legality)> {
+        Tile::all_for_board(self.width, self.height)
+            .filter_map(|tile| match self.get(tile.0) {
+                Slot::Empty(legality) => Some((tile.0, legality)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Renders the board to a string, optionally with A-I / 1-12 coordinate labels, ANSI chain
+    /// colours and a set of highlighted points which are drawn inside brackets.
+    /// The grid's own `indicators` are always highlighted.
+    pub fn render(&self, opts: &RenderOptions) -> String {
+        let mut out = String::new();
+
+        if opts.coordinates {
+            out.push_str("  ");
+            for x in 0..self.width as i8 {
+                let label = match opts.coordinate_style {
+                    CoordinateStyle::RowLetter => (x + 1).to_string(),
+                    CoordinateStyle::ColumnLetter => map_i8_to_letter(x + 1).unwrap_or('?').to_string(),
+                };
+                out.push_str(&format!("{: ^3}", label));
+            }
+            out.push('\n');
+        }
 
-#[allow(unused_must_use)]
-impl Display for Grid {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for y in 0..self.height as i8 {
+            if opts.coordinates {
+                match opts.coordinate_style {
+                    CoordinateStyle::RowLetter => {
+                        out.push(map_i8_to_letter(y + 1).unwrap_or('?'));
+                        out.push(' ');
+                    }
+                    CoordinateStyle::ColumnLetter => out.push_str(&format!("{: <2}", y + 1)),
+                }
+            }
+
             for x in 0..self.width as i8 {
                 let pt = Point { x, y };
-                match self.get(pt) {
-                    Slot::Empty(legality) => {
-                        match legality {
-                            Legality::Legal => write!(f, "□", ),
-                            Legality::TemporarilyIllegal => write!(f, "▫", ),
-                            Legality::PermanentIllegal => write!(f, "▪", ),
-                        };
-                    }
-                    Slot::NoChain => {
-                        write!(f, "■", );
-                    }
-                    Slot::Limbo => {
-                        write!(f, "○", );
-                    }
-                    Slot::Chain(chain) => {
-                        write!(f, "{}", chain.initial());
+                let slot = self.get(pt);
+
+                let symbol = match slot {
+                    Slot::Chain(chain) if opts.colors => {
+                        let (r, g, b) = chain.color();
+                        format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, slot.symbol())
                     }
+                    _ => slot.symbol().to_string(),
+                };
+
+                if opts.highlights.contains(&pt) || self.indicators.contains(&pt) {
+                    out.push_str(&format!("[{}]", symbol));
+                } else {
+                    out.push_str(&format!(" {} ", symbol));
                 }
-                write!(f, "  ", );
             }
-            writeln!(f);
+
+            out.push('\n');
         }
 
-        Ok(())
+        out
+    }
+}
+
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(&RenderOptions::default()))
     }
 }
 
@@ -501,17 +782,100 @@ impl Default for Grid {
             data: Default::default(),
             chain_sizes: Default::default(),
             previously_placed_tile_pt: None,
+            safe_chain_size: DEFAULT_SAFE_CHAIN_SIZE,
+            game_ending_chain_size: DEFAULT_GAME_ENDING_CHAIN_SIZE,
+            num_chains: NUM_CHAINS,
+            indicators: Default::default(),
         }
     }
 }
 
 
+/// The distinct chains around a point, there can only ever be four.
+#[derive(Copy, Clone, Debug)]
+pub struct NeighbouringChains {
+    chains: [Chain; 4],
+    len: u8,
+}
+
+impl NeighbouringChains {
+    fn insert(&mut self, chain: Chain) {
+        if !self.contains(&chain) {
+            self.chains[self.len as usize] = chain;
+            self.len += 1;
+        }
+    }
+}
+
+impl Default for NeighbouringChains {
+    fn default() -> Self {
+        Self {
+            chains: [CHAIN_ARRAY[0]; 4],
+            len: 0,
+        }
+    }
+}
+
+impl Deref for NeighbouringChains {
+    type Target = [Chain];
+
+    fn deref(&self) -> &Self::Target {
+        &self.chains[..self.len as usize]
+    }
+}
+
+/// Directions on the board as it's rendered, with row A at the top and column 1 on the left.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Direction {
+    /// towards row A
+    North,
+    /// towards higher numbered columns
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// The order `Grid::neighbours` lists neighbours in.
+    pub const NEIGHBOUR_ORDER: [Direction; 4] = [Direction::South, Direction::East, Direction::North, Direction::West];
+
+    /// `(x, y)` to add to a point to move one cell this way.
+    pub fn offset(&self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    /// The point one cell this way from `pt`, which may be off the board.
+    pub fn from(&self, pt: Point) -> Point {
+        let (dx, dy) = self.offset();
+        Point { x: pt.x + dx, y: pt.y + dy }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: i8,
     pub y: i8,
 }
 
+/// Row-major, so A1 < A2 < ... < A12 < B1
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 
 impl TryFrom<&str> for Point {
     type Error = TileParseError;
@@ -529,6 +893,7 @@ impl From<Tile> for Point {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Legality {
     Legal,
     TemporarilyIllegal,
@@ -536,6 +901,7 @@ pub enum Legality {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Slot {
     Empty(Legality),
     NoChain,
@@ -543,11 +909,41 @@ pub enum Slot {
     Chain(Chain),
 }
 
+impl Slot {
+    pub fn symbol(&self) -> char {
+        match self {
+            Slot::Empty(legality) => {
+                match legality {
+                    Legality::Legal => '□',
+                    Legality::TemporarilyIllegal => '▫',
+                    Legality::PermanentIllegal => '▪',
+                }
+            }
+            Slot::NoChain => '■',
+            Slot::Limbo => '○',
+            Slot::Chain(chain) => chain.initial(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    pub coordinates: bool,
+    /// which of the rows or columns are labelled with letters when drawing coordinates
+    pub coordinate_style: CoordinateStyle,
+    pub colors: bool,
+    pub highlights: HashSet<Point>,
+}
+
+
 #[cfg(test)]
 mod test {
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
     use crate::tile;
     use crate::chain::Chain;
-    use crate::grid::{Grid, Legality, PlaceTileResult, Slot};
+    use crate::grid::{Direction, Grid, GridError, Legality, PlaceTileResult, Point, RenderOptions, Slot};
+    use crate::tile::{CoordinateStyle, Tile};
 
 
     #[test]
@@ -674,6 +1070,244 @@ mod test {
         assert_eq!(grid.get(tile!("D8")), Slot::Empty(Legality::PermanentIllegal));
     }
 
+    #[test]
+    fn test_lowered_safe_chain_size() {
+        let mut grid = Grid {
+            safe_chain_size: 5,
+            ..Default::default()
+        };
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.place(tile!("A3"));
+        grid.place(tile!("A4"));
+        grid.fill_chain(tile!("A4"), Chain::American);
+
+        grid.place(tile!("C1"));
+        grid.place(tile!("C2"));
+        grid.place(tile!("C3"));
+        grid.place(tile!("C4"));
+        grid.place(tile!("C5"));
+        grid.fill_chain(tile!("C5"), Chain::Tower);
+
+        // american is one tile short of safe, so the gap is still playable
+        assert_eq!(grid.get(tile!("B2")), Slot::Empty(Legality::Legal));
+
+        grid.place(tile!("A5"));
+        assert_eq!(grid.chain_size(Chain::American), 5);
+
+        // two safe chains of size 5 now flank the gap
+        assert_eq!(grid.get(tile!("B2")), Slot::Empty(Legality::PermanentIllegal));
+        assert_eq!(grid.get(tile!("B5")), Slot::Empty(Legality::PermanentIllegal));
+        assert!(!grid.game_ending_chain_exists());
+
+        grid.game_ending_chain_size = 5;
+        assert!(grid.game_ending_chain_exists());
+    }
+
+    #[test]
+    fn test_defunct_order() {
+        let mut grid = Grid::default();
+
+        grid.place(tile!("A2"));
+        grid.place(tile!("A3"));
+        grid.place(tile!("B3"));
+        grid.place(tile!("C3"));
+        grid.fill_chain(tile!("C3"), Chain::Continental);
+
+        grid.place(tile!("E3"));
+        grid.place(tile!("F3"));
+        grid.place(tile!("G3"));
+        grid.fill_chain(tile!("E3"), Chain::Tower);
+
+        grid.place(tile!("D4"));
+        grid.place(tile!("D5"));
+        grid.fill_chain(tile!("D4"), Chain::Festival);
+
+        grid.place(tile!("D1"));
+        grid.place(tile!("D2"));
+        grid.fill_chain(tile!("D2"), Chain::American);
+
+        let PlaceTileResult::Merge { mergers } = grid.place(tile!("D3")) else {
+            panic!("expected a merge");
+        };
+
+        // largest first, then the two chains of size 2 in enum order
+        let defunct: Vec<Chain> = mergers.iter().map(|merger| merger.defunct_chain).collect();
+        assert_eq!(defunct, vec![Chain::Tower, Chain::American, Chain::Festival]);
+        assert!(mergers.iter().all(|merger| merger.merging_chain == Chain::Continental));
+    }
+
+    #[test]
+    fn test_chain_frontier() {
+        let mut grid = Grid::default();
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.place(tile!("B2"));
+        grid.fill_chain(tile!("A1"), Chain::Luxor);
+
+        // B1 is next to both A1 and B2 but only listed once, the chain's own tiles aren't listed
+        // and nothing off the edge of the board is either
+        assert_eq!(grid.chain_frontier(Chain::Luxor), vec![
+            tile!("A3"),
+            tile!("B1"),
+            tile!("B3"),
+            tile!("C2"),
+        ]);
+
+        assert!(grid.chain_frontier(Chain::Tower).is_empty());
+    }
+
+    #[test]
+    fn test_board_stats() {
+        let mut grid = Grid {
+            safe_chain_size: 3,
+            ..Default::default()
+        };
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.place(tile!("A3"));
+        grid.fill_chain(tile!("A1"), Chain::American);
+
+        grid.place(tile!("C1"));
+        grid.place(tile!("C2"));
+        grid.place(tile!("C3"));
+        grid.fill_chain(tile!("C1"), Chain::Tower);
+
+        grid.place(tile!("E1"));
+        grid.place(tile!("E2"));
+        grid.fill_chain(tile!("E1"), Chain::Luxor);
+
+        grid.place(tile!("G5"));
+        grid.place(tile!("I12"));
+
+        let stats = grid.board_stats();
+        assert_eq!(stats.num_chains, 3);
+        assert_eq!(stats.chain_sizes.get(&Chain::American), 3);
+        assert_eq!(stats.chain_sizes.get(&Chain::Luxor), 2);
+        assert_eq!(stats.num_safe_chains, 2);
+        assert_eq!(stats.num_nochain_tiles, 2);
+
+        // B1 to B3 would merge the two safe chains
+        assert_eq!(stats.num_permanently_illegal, 3);
+        assert_eq!(stats.num_temporarily_illegal, 0);
+        assert_eq!(stats.num_legal, 12 * 9 - 10 - 3);
+    }
+
+    #[test]
+    fn test_reduced_chain_count() {
+        let mut grid = Grid {
+            num_chains: 2,
+            ..Default::default()
+        };
+
+        grid.place(tile!("I12"));
+        assert_eq!(grid.available_chains(), vec![Chain::Tower, Chain::Luxor]);
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.fill_chain(tile!("A1"), Chain::Tower);
+        assert_eq!(grid.get(tile!("I11")), Slot::Empty(Legality::Legal));
+
+        // the second chain is the last one, so nothing more can be founded next to I12
+        grid.place(tile!("C1"));
+        grid.place(tile!("C2"));
+        grid.fill_chain(tile!("C1"), Chain::Luxor);
+        assert_eq!(grid.num_available_chains(), 0);
+        assert_eq!(grid.get(tile!("I11")), Slot::Empty(Legality::TemporarilyIllegal));
+        assert_eq!(grid.get(tile!("H12")), Slot::Empty(Legality::TemporarilyIllegal));
+        assert_eq!(grid.get(tile!("E1")), Slot::Empty(Legality::Legal));
+
+        // neither of the two chains is safe, but they're every chain there is
+        assert!(!grid.all_chains_are_safe());
+        grid.safe_chain_size = 2;
+        assert!(grid.all_chains_are_safe());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut grid = Grid::default();
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.fill_chain(tile!("A1"), Chain::Tower);
+        grid.place(tile!("C5"));
+
+        let snapshot = grid.snapshot();
+        assert_eq!(snapshot.slots.len(), 12 * 9);
+        assert_eq!(snapshot.get(tile!("A2")), Slot::Chain(Chain::Tower));
+        assert_eq!(snapshot.get(tile!("C5")), Slot::NoChain);
+
+        grid.place(tile!("A3"));
+        grid.place(tile!("C6"));
+        grid.fill_chain(tile!("C6"), Chain::Luxor);
+        assert_ne!(grid.snapshot(), snapshot);
+
+        grid.restore(&snapshot);
+        assert_eq!(grid.snapshot(), snapshot);
+        assert_eq!(grid.chain_size(Chain::Tower), 2);
+        assert_eq!(grid.chain_size(Chain::Luxor), 0);
+        #[cfg(debug_assertions)]
+        assert!(grid.verify_chain_sizes());
+    }
+
+    #[test]
+    fn test_directions() {
+        let mut grid = Grid::default();
+        grid.place(tile!("A5"));
+        grid.place(tile!("B6"));
+        grid.place(tile!("C5"));
+        grid.place(tile!("B4"));
+
+        let center: Point = tile!("B5");
+        assert_eq!(Direction::North.from(center), tile!("A5"));
+        assert_eq!(Direction::East.from(center), tile!("B6"));
+        assert_eq!(Direction::South.from(center), tile!("C5"));
+        assert_eq!(Direction::West.from(center), tile!("B4"));
+
+        for dir in Direction::NEIGHBOUR_ORDER {
+            assert_eq!(grid.neighbour(center, dir), Slot::NoChain);
+        }
+        assert_eq!(grid.neighbour(tile!("A5"), Direction::North), Slot::Empty(Legality::Legal));
+
+        // the array forms list the neighbours in the same order
+        let points = grid.neighbouring_points(center);
+        assert_eq!(points, [tile!("C5"), tile!("B6"), tile!("A5"), tile!("B4")]);
+        assert_eq!(grid.neighbours(center), points.map(|pt| grid.get(pt)));
+    }
+
+    #[test]
+    fn test_legality_map() {
+        let mut grid = Grid {
+            safe_chain_size: 3,
+            ..Default::default()
+        };
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.place(tile!("A3"));
+        grid.fill_chain(tile!("A1"), Chain::American);
+
+        grid.place(tile!("C1"));
+        grid.place(tile!("C2"));
+        grid.place(tile!("C3"));
+        grid.fill_chain(tile!("C1"), Chain::Tower);
+
+        let legality_map = grid.legality_map();
+
+        assert_eq!(legality_map.len(), 12 * 9 - 6);
+        assert_eq!(legality_map[0], (tile!("A4"), Legality::Legal));
+
+        let permanently_illegal: Vec<Point> = legality_map
+            .iter()
+            .filter(|(_, legality)| *legality == Legality::PermanentIllegal)
+            .map(|(pt, _)| *pt)
+            .collect();
+
+        assert_eq!(permanently_illegal, vec![tile!("B1"), tile!("B2"), tile!("B3")]);
+    }
+
     #[test]
     fn test_temporary_illegal_tile() {
         let mut grid = Grid::default();
@@ -833,4 +1467,207 @@ mod test {
         // should only have one chain, luxor should be removed from map
         assert_eq!(grid.chain_sizes[&Chain::American], 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut grid = Grid::default();
+
+        // I12 is the far corner of a 12x9 board
+        assert!(!grid.is_pt_out_of_bounds(tile!("I12")));
+        assert!(!grid.is_pt_out_of_bounds(Point { x: 11, y: 0 }));
+        assert!(grid.is_pt_out_of_bounds(Point { x: 12, y: 0 }));
+        assert!(grid.is_pt_out_of_bounds(Point { x: 0, y: 9 }));
+        assert!(grid.is_pt_out_of_bounds(Point { x: -1, y: 0 }));
+
+        assert_eq!(grid.place(tile!("A12")), PlaceTileResult::Proceed);
+        assert_eq!(grid.get(tile!("A12")), Slot::NoChain);
+
+        // off-board neighbours of edge tiles read as empty
+        assert_eq!(grid.neighbours(tile!("A12")), [
+            Slot::Empty(Legality::Legal),
+            Slot::Empty(Legality::Legal),
+            Slot::Empty(Legality::Legal),
+            Slot::Empty(Legality::Legal),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "A13 is not on the board")]
+    fn test_place_out_of_bounds() {
+        let mut grid = Grid::default();
+        grid.place(Tile::new(12, 0));
+    }
+
+    #[test]
+    fn test_try_place() {
+        let mut grid = Grid::default();
+        for tile in [Tile::new(12, 0), Tile::new(0, 9), Tile::new(-1, 0)] {
+            assert_eq!(grid.try_place(tile), Err(GridError::OutOfBounds(tile)));
+        }
+        assert!(grid.data.is_empty());
+
+        assert_eq!(grid.try_place(tile!("A1")), Ok(PlaceTileResult::Proceed));
+        assert_eq!(grid.get(tile!("A1")), Slot::NoChain);
+    }
+
+    #[test]
+    fn test_largest_chain() {
+        let mut grid = Grid::default();
+        assert_eq!(grid.largest_chain(), None);
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.fill_chain(tile!("A1"), Chain::Festival);
+
+        grid.place(tile!("C1"));
+        grid.place(tile!("C2"));
+        grid.place(tile!("C3"));
+        grid.fill_chain(tile!("C1"), Chain::Continental);
+
+        grid.place(tile!("E1"));
+        grid.place(tile!("E2"));
+        grid.place(tile!("E3"));
+        grid.fill_chain(tile!("E1"), Chain::Luxor);
+
+        // luxor and continental are tied, luxor comes first
+        assert_eq!(grid.largest_chain(), Some((Chain::Luxor, 3)));
+
+        grid.place(tile!("C4"));
+        assert_eq!(grid.largest_chain(), Some((Chain::Continental, 4)));
+
+        let sizes = grid.chain_sizes_snapshot();
+        assert_eq!(sizes[&Chain::Festival], 2);
+        assert_eq!(sizes[&Chain::Continental], 4);
+        assert_eq!(sizes[&Chain::Luxor], 3);
+        assert_eq!(sizes[&Chain::Tower], 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = Grid::new(3, 2);
+
+        grid.place(tile!("A1"));
+        grid.place(tile!("A2"));
+        grid.fill_chain(tile!("A1"), Chain::American);
+        grid.place(tile!("B3"));
+
+        let plain = grid.render(&RenderOptions::default());
+        assert_eq!(plain, " A  A  □ \n □  □  ■ \n");
+
+        let mut opts = RenderOptions {
+            coordinates: true,
+            ..Default::default()
+        };
+        opts.highlights.insert(tile!("B1"));
+
+        let labelled = grid.render(&opts);
+        assert_eq!(labelled, concat!(
+            "   1  2  3 \n",
+            "A  A  A  □ \n",
+            "B [□] □  ■ \n",
+        ));
+
+        opts.colors = true;
+        let coloured = grid.render(&opts);
+        assert!(coloured.contains("\x1b[38;2;40;80;200mA\x1b[0m"));
+
+        opts.colors = false;
+        opts.coordinate_style = CoordinateStyle::ColumnLetter;
+        let column_lettered = grid.render(&opts);
+        assert_eq!(column_lettered, concat!(
+            "   A  B  C \n",
+            "1  A  A  □ \n",
+            "2 [□] □  ■ \n",
+        ));
+    }
+
+    #[test]
+    fn test_indicators() {
+        let mut grid = Grid::new(3, 2);
+        grid.place(tile!("A1"));
+
+        let before = grid.to_string();
+
+        grid.indicators.insert(tile!("A1"));
+        grid.indicators.insert(tile!("B2"));
+
+        assert_ne!(before, grid.to_string());
+        assert_eq!(grid.to_string(), "[■] □  □ \n □ [□] □ \n");
+    }
+
+    /// Fills boards by placing every tile in a random order, resolving each placement the way a
+    /// game would, and calls `check` after every tile with the seed it came from.
+    fn for_each_random_placement(num_seeds: u64, mut check: impl FnMut(&Grid, Tile, u64)) {
+        for seed in 0..num_seeds {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let mut grid = Grid::default();
+
+            let mut tiles: Vec<Tile> = Tile::all_for_board(grid.width, grid.height).collect();
+            tiles.shuffle(&mut rng);
+
+            for tile in tiles {
+                match grid.place(tile) {
+                    PlaceTileResult::SelectAvailableChain => {
+                        let chain = *grid.available_chains().choose(&mut rng).expect("an available chain");
+                        grid.fill_chain(tile.0, chain);
+                    }
+                    PlaceTileResult::DecideTieBreak { tied_chains } => {
+                        let chain = *tied_chains.choose(&mut rng).expect("a tied chain");
+                        grid.fill_chain(tile.0, chain);
+                    }
+                    PlaceTileResult::Merge { mergers } => {
+                        grid.fill_chain(tile.0, mergers[0].merging_chain);
+                    }
+                    PlaceTileResult::Proceed |
+                    PlaceTileResult::Illegal { .. } => {}
+                }
+
+                check(&grid, tile, seed);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_chain_sizes_stay_consistent() {
+        for_each_random_placement(50, |grid, tile, seed| {
+            assert!(grid.verify_chain_sizes(), "chain sizes drifted after placing {} (seed {})", tile, seed);
+        });
+    }
+
+    #[test]
+    fn test_incremental_legality_matches_rescan() {
+        for_each_random_placement(50, |grid, tile, seed| {
+            // work out every empty slot's legality from scratch
+            for pt in Tile::all_for_board(grid.width, grid.height).map(|tile| tile.0) {
+                if let Slot::Empty(legality) = grid.get(pt) {
+                    let rescanned = match grid._is_illegal_tile(Tile(pt)) {
+                        (false, _) => Legality::Legal,
+                        (true, false) => Legality::TemporarilyIllegal,
+                        (true, true) => Legality::PermanentIllegal,
+                    };
+
+                    assert_eq!(legality, rescanned, "{} after placing {} (seed {})", Tile(pt), tile, seed);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_chains_in_slots() {
+        use itertools::Itertools;
+
+        for_each_random_placement(20, |grid, _, _| {
+            // the allocating version this replaced
+            for pt in Tile::all_for_board(grid.width, grid.height).map(|tile| tile.0) {
+                let neighbours = grid.neighbours(pt);
+                let expected: Vec<Chain> = neighbours.iter().filter_map(|slot| match slot {
+                    Slot::Chain(chain) => Some(*chain),
+                    _ => None,
+                }).unique().collect();
+
+                assert_eq!(&*grid.chains_in_slots(&neighbours), expected.as_slice());
+            }
+        });
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Acquire, Action, Options};
+
+/// Every state a game passed through, for stepping back and forth in a replay.
+///
+/// Each state is stored rather than replayed on demand, which makes `at_step` free at the cost of
+/// keeping one (lightweight) copy of the game per action, a few KB each and a few hundred states
+/// for a full game. Replaying from the seed would instead cost a pass over every earlier action
+/// each time a step is looked at.
+#[derive(Clone)]
+pub struct GameHistory {
+    states: Vec<Acquire>,
+}
+
+impl GameHistory {
+    pub fn new(initial: &Acquire) -> Self {
+        Self {
+            states: vec![initial.lightweight_clone()],
+        }
+    }
+
+    /// Replays `actions` from a freshly dealt game.
+    pub fn replay(seed: u64, options: &Options, actions: &[Action]) -> Self {
+        let mut history = Self::new(&Acquire::from_seed(seed, options));
+        for action in actions {
+            history.push(action.clone());
+        }
+
+        history
+    }
+
+    /// Applies the action to the latest state and records a lightweight copy of the result.
+    pub fn push(&mut self, action: Action) -> &Acquire {
+        let next = self.latest().apply_action(action).lightweight_clone();
+        self.states.push(next);
+        self.latest()
+    }
+
+    pub fn latest(&self) -> &Acquire {
+        self.states.last().expect("history always holds the initial state")
+    }
+
+    /// The initial state followed by the state after each action.
+    pub fn states(&self) -> impl Iterator<Item = &Acquire> {
+        self.states.iter()
+    }
+
+    /// The state after `step` actions have been applied, step 0 being the initial deal.
+    pub fn at_step(&self, step: u16) -> Option<&Acquire> {
+        self.states.get(step as usize)
+    }
+
+    /// The number of actions applied so far.
+    pub fn num_steps(&self) -> usize {
+        self.states.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Acquire, GameHistory, Options};
+
+    #[test]
+    fn test_at_step() {
+        let options = Options::default();
+        let mut history = GameHistory::new(&Acquire::from_seed(2, &options));
+
+        let mut actions = vec![];
+        for _ in 0..8 {
+            let action = history.latest().actions().remove(0);
+            actions.push(action.clone());
+            history.push(action);
+        }
+
+        assert_eq!(history.num_steps(), 8);
+        assert_eq!(history.states().count(), 9);
+        assert!(history.at_step(9).is_none());
+
+        let mut replayed = Acquire::from_seed(2, &options);
+        for action in &actions[0..3] {
+            replayed = replayed.apply_action(action.clone());
+        }
+
+        assert_eq!(history.at_step(3).unwrap().to_bytes(), replayed.to_bytes());
+        assert_eq!(GameHistory::replay(2, &options, &actions).at_step(3).unwrap().to_bytes(), replayed.to_bytes());
+
+        // recording a logged game doesn't carry the log into every stored state
+        let mut logged = GameHistory::new(&Acquire::from_seed(2, &options).with_logging());
+        logged.push(actions[0].clone());
+        assert!(logged.states().all(|state| state.log().is_empty()));
+    }
+}
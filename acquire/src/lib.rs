@@ -5,18 +5,48 @@ mod stock;
 mod player;
 mod chain;
 mod ai;
-
-use tile::Tile;
+mod tournament;
+mod snapshot;
+mod view;
+mod log;
+mod options;
+mod history;
+mod error;
+mod diff;
+mod transcript;
+mod geometry;
+
+pub use tile::{CoordinateStyle, Tile, TileParseError};
 use std::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "testing")]
+use std::collections::VecDeque;
+use std::sync::Arc;
 use itertools::Itertools;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand::seq::SliceRandom;
-use chain::{Chain, CHAIN_ARRAY};
+use chain::CHAIN_ARRAY;
 use player::Player;
-use crate::chain::ChainTable;
-use crate::grid::{Grid, Legality, PlaceTileResult, Slot};
+pub use player::PlayerStats;
+use crate::grid::PlaceTileResult;
 use crate::stock::Stocks;
 
+pub use ai::HeuristicAgent;
+pub use chain::{Chain, ChainTable};
+pub use error::AcquireError;
+pub use geometry::{adjacent_points, in_bounds, manhattan};
+pub use diff::{PlayerDiff, StateDiff};
+pub use grid::{BoardStats, Direction, Grid, GridError, GridSnapshot, Legality, NeighbouringChains, Point, RenderOptions, Slot};
+pub use history::GameHistory;
+pub use log::{LogEntry, PublicTranscript};
+#[cfg(feature = "serde")]
+pub use log::ActionLine;
+pub use money::{FoundingInfo, PurchaseOption, PurchaseParseError, TieBreak, DEFAULT_TIE_BREAKS};
+pub use options::{BonusRounding, Options, OptionsError, StockSupply};
+pub use snapshot::DecodeError;
+pub use tournament::{run_match, Agent, MatchResult, PlayerMatchStats, RandomAgent};
+pub use transcript::{Inconsistency, TranscriptError};
+pub use view::{BoardCell, CellKind, PlayerView, PublicPlayer, SpectatorState};
+
 
 #[derive(Clone)]
 pub struct Acquire {
@@ -29,52 +59,54 @@ pub struct Acquire {
     turn: u16,
     step: u16,
     terminated: bool,
-}
-
-pub struct Options {
-    num_players: u8,
-    num_tiles: u8,
-    grid_width: u8,
-    grid_height: u8,
-    num_stock: u8,
-    starting_money: u32,
-}
-
-impl Default for Options {
-    fn default() -> Self {
-        Self {
-            num_players: 4,
-            num_tiles: 6,
-            grid_width: 12,
-            grid_height: 9,
-            num_stock: 25,
-            starting_money: 6000,
-        }
-    }
+    options: Options,
+    log: Option<Vec<LogEntry>>,
+    /// the game as it was before the tile which started the merge in progress was placed
+    pre_merge: Option<Arc<Acquire>>,
 }
 
 
 impl Acquire {
+    /// Deals a new game, panics if the options fail `Options::validate`.
     pub fn new<R: Rng>(rng: &mut R, options: &Options) -> Self {
-        let grid = Grid::new(options.grid_width, options.grid_height);
-
-        let mut tiles = vec![];
-        for y in 0..grid.height as i8 {
-            for x in 0..grid.width as i8 {
-                tiles.push(Tile::new(x, y));
-            }
+        if let Err(err) = options.validate() {
+            panic!("invalid options: {}", err);
         }
 
+        let mut grid = Grid::new(options.grid_width, options.grid_height);
+        grid.safe_chain_size = options.safe_chain_size;
+        grid.game_ending_chain_size = options.game_ending_chain_size;
+        grid.num_chains = options.num_chains;
+
+        let mut tiles: Vec<Tile> = Tile::all_for_board(grid.width, grid.height).collect();
+
         tiles.shuffle(rng);
 
-        let players = (0..options.num_players).map(|id| Player {
+        let mut players: Vec<Player> = (0..options.num_players).map(|id| Player {
             id: PlayerId(id),
-            tiles: (0..options.num_tiles).map(|_| tiles.remove(0)).collect(),
+            tiles: vec![],
             stocks: Stocks::new(0),
             money: options.starting_money,
+            stats: PlayerStats::default(),
         }).collect();
 
-        let stocks = Stocks::new(options.num_stock);
+        if options.deal_one_at_a_time {
+            for _ in 0..options.num_tiles {
+                for player in &mut players {
+                    player.tiles.push(tiles.remove(0));
+                }
+            }
+        } else {
+            for player in &mut players {
+                player.tiles = (0..options.num_tiles).map(|_| tiles.remove(0)).collect();
+            }
+        }
+
+        let stocks = Stocks::from_amounts(ChainTable(CHAIN_ARRAY.map(|chain| options.num_stock.amount(chain))));
+
+        let current_player_id = options.starting_player.unwrap_or_else(|| {
+            PlayerId(rng.gen_range(0..options.num_players))
+        });
 
         Self {
             phase: Phase::AwaitingTilePlacement,
@@ -82,10 +114,58 @@ impl Acquire {
             tiles,
             stocks,
             grid,
-            current_player_id: PlayerId(0),
+            current_player_id,
             turn: 1,
             step: 0,
             terminated: false,
+            options: options.clone(),
+            log: None,
+            pre_merge: None,
+        }
+    }
+
+    /// Shorthand for `new` with a `ChaCha8Rng` seeded from `seed`, so the same seed always
+    /// deals the same game.
+    pub fn from_seed(seed: u64, options: &Options) -> Self {
+        Self::new(&mut rand_chacha::ChaCha8Rng::seed_from_u64(seed), options)
+    }
+
+    /// A clone for search and simulation, which skips the log, the grid's indicators and the
+    /// state `cancel_merge` would go back to. Playing on from it behaves exactly like playing on
+    /// from a full clone, except that the merge in progress can't be cancelled.
+    pub fn lightweight_clone(&self) -> Acquire {
+        Acquire {
+            phase: self.phase.clone(),
+            players: self.players.clone(),
+            tiles: self.tiles.clone(),
+            stocks: self.stocks.clone(),
+            grid: self.grid.clone_without_indicators(),
+            current_player_id: self.current_player_id,
+            turn: self.turn,
+            step: self.step,
+            terminated: self.terminated,
+            options: self.options.clone(),
+            log: None,
+            pre_merge: None,
+        }
+    }
+
+    /// The current actions numbered for a frontend to list, the numbers are indices into
+    /// `actions()`.
+    pub fn action_menu(&self) -> Vec<(usize, String)> {
+        self.actions()
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| (idx, self.format_action(action)))
+            .collect()
+    }
+
+    /// Describes the action the way `Display` does, but with tiles written in this game's
+    /// `coordinate_style`.
+    pub fn format_action(&self, action: &Action) -> String {
+        match action {
+            Action::PlaceTile(player_id, tile) => format!("Player {} places tile {}", player_id.0, self.format_tile(*tile)),
+            _ => action.to_string(),
         }
     }
 
@@ -115,7 +195,7 @@ impl Acquire {
     #[inline(never)]
     fn tile_placement_actions(&self) -> Vec<Action> {
         let player = self.get_player_by_id(self.current_player_id);
-        player.tiles.iter().filter_map(|tile| {
+        player.tiles.iter().sorted().filter_map(|tile| {
             match self.grid.get(tile.0) {
                 Slot::Empty(legality) => {
                     match legality {
@@ -142,6 +222,42 @@ impl Acquire {
         &self.players
     }
 
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    /// Parses a tile written in this game's `coordinate_style`.
+    pub fn parse_tile(&self, value: &str) -> Result<Tile, TileParseError> {
+        Tile::parse(value, self.options.coordinate_style)
+    }
+
+    /// Writes a tile in this game's `coordinate_style`.
+    pub fn format_tile(&self, tile: Tile) -> String {
+        tile.format(self.options.coordinate_style)
+    }
+
+
+    /// The chains the current player can pick from for the chain they just founded.
+    pub fn creatable_chains(&self) -> Result<Vec<Chain>, AcquireError> {
+        match self.phase {
+            Phase::AwaitingChainCreationSelection => Ok(self.grid.available_chains()),
+            _ => Err(AcquireError::NotCreatingChain(self.phase.clone())),
+        }
+    }
+
+    /// Every chain is already on the board, so until one is merged away no tile can found a
+    /// new chain and any that would are temporarily illegal.
+    pub fn no_chains_available(&self) -> bool {
+        self.grid.num_available_chains() == 0
+    }
 
     #[inline(never)]
     fn chain_selection_actions(&self) -> Vec<Action> {
@@ -154,7 +270,8 @@ impl Acquire {
     fn merge_actions(&self, merging_player_id: &PlayerId, merge_phase: &MergePhase, mergers_remaining: &[MergingChains]) -> Vec<Action> {
         match merge_phase {
             MergePhase::AwaitingTiebreakSelection { tied_chains } => {
-                tied_chains.iter().map(|chain| {
+                // tied chains are listed in chain order, whichever way round they touch the placed tile
+                tied_chains.iter().sorted().map(|chain| {
                     Action::SelectChainForTiebreak(*merging_player_id, *chain)
                 }).collect()
             }
@@ -186,9 +303,9 @@ impl Acquire {
     #[inline(never)]
     fn stock_purchase_actions(&self) -> Vec<Action> {
         self.purchasable_combinations(self.current_player_id)
-            .iter()
+            .into_iter()
             .map(|buy| {
-                Action::PurchaseStock(self.current_player_id, *buy)
+                Action::PurchaseStock(self.current_player_id, buy)
             })
             .collect()
     }
@@ -198,7 +315,7 @@ impl Acquire {
 
 
         #[cfg(test)]
-        println!("S{}: {}", game.step, action);
+        println!("S{}: {}", game.step, game.format_action(&action));
 
         match action {
             Action::PlaceTile(player_id, tile) => {
@@ -207,16 +324,21 @@ impl Acquire {
                 // remove tile from player inventory
                 let tile_idx = player.tiles.iter().position(|t| *t == tile).unwrap();
                 let tile = player.tiles.remove(tile_idx);
+                player.stats.tiles_placed += 1;
 
                 // after the tile is placed, there are several branches to consider
                 // which changes which phase the game moves to
                 let result = game.grid.place(tile);
+                if matches!(result, PlaceTileResult::DecideTieBreak { .. } | PlaceTileResult::Merge { .. }) {
+                    game.get_player_by_id_mut(player_id).stats.mergers_initiated += 1;
+                    game.pre_merge = Some(Arc::new(self.lightweight_clone()));
+                }
                 match result {
                     // nothing special happens, the game proceeds to the next player
                     PlaceTileResult::Proceed => {
                         game.phase = Phase::AwaitingStockPurchase;
                         // shortcut the purchase of stock when there are no chains to buy
-                        if game.grid.existing_chains().is_empty() {
+                        if game.grid.existing_chains().is_empty() && !game.options.always_show_purchase_phase {
                             game.player_take_tile(player_id);
                             game.move_to_next_player_who_can_play_a_tile();
                         }
@@ -237,34 +359,15 @@ impl Acquire {
                         };
                     }
                     // the tile placed merged two chains together without the need for a tiebreak
-                    PlaceTileResult::Merge { mut mergers } => {
-                        for merger in &mut mergers {
-                            let num = self.num_players_with_stock_in_chain(merger.defunct_chain);
-                            merger.num_remaining_players_to_merge = Some(num);
-                        }
-
-                        mergers.retain(|merger| merger.num_remaining_players_to_merge != Some(0));
+                    PlaceTileResult::Merge { mergers } => {
+                        let surviving_chain = mergers[0].merging_chain;
+                        game.phase = Phase::Merge {
+                            merging_player_id: self.current_player_id,
+                            phase: MergePhase::AwaitingMergeDecision,
+                            mergers_remaining: mergers,
+                        };
 
-                        // apparently nobody benefits from any of the mergers
-                        if mergers.is_empty() {
-                            game.phase = Phase::AwaitingStockPurchase;
-                        } else {
-                            let first_defunct_chain = mergers[0].defunct_chain;
-
-                            if let Some(next_merging_player_id) = self.next_merging_player_id(first_defunct_chain) {
-                                game.provide_bonuses(first_defunct_chain);
-
-                                game.phase = Phase::Merge {
-                                    merging_player_id: next_merging_player_id,
-                                    phase: MergePhase::AwaitingMergeDecision,
-                                    mergers_remaining: mergers,
-                                };
-                            } else {
-                                // somehow no one has any stake in the hotel.
-                                // only possible with house rules allowing sale of stock
-                                game.phase = Phase::AwaitingStockPurchase;
-                            }
-                        }
+                        game.start_next_merger(surviving_chain);
                     }
                     // the tile was placed illegally
                     PlaceTileResult::Illegal { .. } => {
@@ -278,28 +381,29 @@ impl Acquire {
                 game.grid.fill_chain(pt, chain);
                 game.phase = Phase::AwaitingStockPurchase;
 
-                // free stock for creating a chain
+                // free stock for creating a chain, as long as the bank has any left
                 if game.stocks.withdraw(chain, 1).is_ok() {
                     game.get_player_by_id_mut(player_id).stocks.deposit(chain, 1);
                 }
             }
 
-            Action::PurchaseStock(player_id, buys) => {
+            Action::PurchaseStock(player_id, ref buys) => {
                 for buy in buys {
-                    match buy {
+                    match *buy {
                         BuyOption::None => {}
                         BuyOption::Chain(chain) => {
                             game.stocks.withdraw(chain, 1).expect("enough stock to withdraw");
 
                             let player = game.get_player_by_id_mut(player_id);
                             player.stocks.deposit(chain, 1);
-                            player.money -= money::chain_value(chain, self.grid.chain_size(chain))
+                            player.money -= money::chain_value(chain, self.grid.chain_size(chain));
+                            player.stats.shares_bought += 1;
                         }
                     }
                 }
 
                 game.player_take_tile(player_id);
-                game.player_trade_in_illegal_tiles(player_id);
+                game.discard_dead_tiles(player_id);
 
                 if game.may_terminate() {
                     game.phase = Phase::AwaitingGameTerminationDecision;
@@ -312,26 +416,41 @@ impl Acquire {
                 match &mut game.phase {
                     Phase::Merge { phase: merge_phase, mergers_remaining, .. } => {
                         if let MergePhase::AwaitingTiebreakSelection { tied_chains } = merge_phase {
-                            for defunct_chain in tied_chains.iter().filter(|chain| **chain != tiebreak_chain) {
-                                // use self here to avoid interior mutability issues
-                                let num = self.num_players_with_stock_in_chain(*defunct_chain);
+                            assert!(
+                                tied_chains.contains(&tiebreak_chain),
+                                "{:?} is not one of the chains tied for the merge ({:?})", tiebreak_chain, tied_chains
+                            );
+
+                            let largest_tied_size = tied_chains.iter().map(|chain| self.grid.chain_size(*chain)).max();
+                            assert_eq!(
+                                Some(self.grid.chain_size(tiebreak_chain)), largest_tied_size,
+                                "{:?} is not tied for the largest chain in the merge", tiebreak_chain
+                            );
+
+                            // the placed tile may also touch smaller chains that weren't part of the tie,
+                            // they're defunct too so the mergers are rebuilt from all of its neighbours
+                            let pt = self.grid.previously_placed_tile_pt.expect("a previously placed tile");
+                            let neighbouring_chains = self.grid.chains_in_slots(&self.grid.neighbours(pt));
+                            let mut defunct_chains: Vec<Chain> = neighbouring_chains.iter().filter(|chain| **chain != tiebreak_chain).copied().collect();
+                            self.grid.sort_defunct_chains(&mut defunct_chains);
+
+                            for defunct_chain in &defunct_chains {
                                 mergers_remaining.push(MergingChains {
                                     merging_chain: tiebreak_chain,
                                     defunct_chain: *defunct_chain,
-                                    num_remaining_players_to_merge: Some(num),
+                                    num_remaining_players_to_merge: None,
                                 });
                             }
 
                             *merge_phase = MergePhase::AwaitingMergeDecision;
-
-                            let first_defunct_chain = mergers_remaining[0].defunct_chain;
-                            game.provide_bonuses(first_defunct_chain);
                         } else {
                             panic!("supposed to be awaiting a tiebreak")
                         }
                     }
                     _ => panic!("phase should be 'Merge' already")
                 }
+
+                game.start_next_merger(tiebreak_chain);
             }
 
             Action::DecideMerge { decision, merging_player_id: action_merging_player_id } => {
@@ -357,42 +476,22 @@ impl Acquire {
 
                 // need to do this in a second step due to borrowing rules
                 if let Phase::Merge { merging_player_id, mergers_remaining, .. } = &mut game.phase {
-                    if let Some(next_merge_player_id) = next_merging_player_id {
-                        *merging_player_id = next_merge_player_id;
-
-                        let current_merger = &mut mergers_remaining[0];
-                        let num_remaining_players_to_merge = current_merger.num_remaining_players_to_merge.as_mut().unwrap();
-                        *num_remaining_players_to_merge -= 1;
-
-                        // finished the merge
-                        if *num_remaining_players_to_merge == 0 {
-                            // strike off this merge, if there's another then we continue,
-                            // everything should work the same for merge 2+
-                            let merger = mergers_remaining.remove(0);
-
-                            *merging_player_id = self.current_player_id;
-
-                            // if there are no more mergers left to do,
-                            // we can move on to the stock purchase phase
-                            if mergers_remaining.is_empty() {
-                                game.phase = Phase::AwaitingStockPurchase;
-                                game.grid.fill_chain(game.grid.previously_placed_tile_pt.expect("a previously placed tile"), merger.merging_chain);
-                            }
-                        }
-                    } else {
-                        let merger = mergers_remaining.remove(0);
-
-                        *merging_player_id = self.current_player_id;
+                    let finished_merger = match next_merging_player_id {
+                        Some(next_merge_player_id) => {
+                            *merging_player_id = next_merge_player_id;
 
-                        // if there are no more mergers left to do,
-                        // we can move on to the stock purchase phase
-                        if mergers_remaining.is_empty() {
-                            game.phase = Phase::AwaitingStockPurchase;
-                            game.grid.fill_chain(game.grid.previously_placed_tile_pt.expect("a previously placed tile"), merger.merging_chain);
-                        } else {
-                            let first_defunct_chain = mergers_remaining[0].defunct_chain;
-                            game.provide_bonuses(first_defunct_chain);
+                            let num_remaining_players_to_merge = mergers_remaining[0].num_remaining_players_to_merge.as_mut().unwrap();
+                            *num_remaining_players_to_merge -= 1;
+                            *num_remaining_players_to_merge == 0
                         }
+                        None => true,
+                    };
+
+                    // strike off this merge, if there's another then we continue,
+                    // everything should work the same for merge 2+
+                    if finished_merger {
+                        let merger = mergers_remaining.remove(0);
+                        game.start_next_merger(merger.merging_chain);
                     }
                 }
             }
@@ -407,6 +506,20 @@ impl Acquire {
             }
         }
 
+        if !matches!(game.phase, Phase::Merge { .. }) {
+            game.pre_merge = None;
+        }
+
+        if let Some(log) = &mut game.log {
+            log.push(LogEntry {
+                step: self.step,
+                turn: self.turn,
+                player_id: action.player_id(),
+                action,
+                phase_after: game.phase.clone(),
+            });
+        }
+
         if game.terminated {
             return game;
         }
@@ -416,6 +529,60 @@ impl Acquire {
         game
     }
 
+    /// Applies actions chosen by `policy` until the current turn is over, covering the tile
+    /// placement, any chain founding, merge decisions (which the policy makes on behalf of
+    /// whoever is merging), the stock purchase and the decision to end the game.
+    pub fn play_turn<F: FnMut(&Acquire, &[Action]) -> Action>(&self, mut policy: F) -> Acquire {
+        let turn = self.turn;
+        let mut game = self.clone();
+
+        while !game.terminated && game.turn == turn {
+            let actions = game.actions();
+            let action = policy(&game, &actions);
+            game = game.apply_action(action);
+        }
+
+        game
+    }
+
+    /// Whether there is any legal action in the current state, regardless of whether the game
+    /// has been flagged as terminated.
+    pub fn has_actions(&self) -> bool {
+        match self.phase {
+            Phase::AwaitingTilePlacement => self.player_has_any_valid_tiles(self.current_player_id),
+            _ => !self.actions().is_empty(),
+        }
+    }
+
+    /// The player whose decision it is. During a merge that's the stakeholder currently deciding
+    /// what to do with their defunct shares, who can be someone other than the player whose turn
+    /// it is.
+    pub fn active_player(&self) -> PlayerId {
+        match self.phase {
+            Phase::Merge { merging_player_id, .. } => merging_player_id,
+            _ => self.current_player_id,
+        }
+    }
+
+    /// Whether the chains on the board are stuck as they are for the rest of the game. That's
+    /// when none of the playable tiles left, in hand or in the bag, would grow, merge or found a
+    /// chain, either now or once another of them has been placed next to it.
+    pub fn is_stalemate(&self) -> bool {
+        let remaining: ahash::HashSet<Tile> = self.players
+            .iter()
+            .flat_map(|player| player.tiles.iter())
+            .chain(self.tiles.iter())
+            .filter(|tile| self.grid.get(tile.0) == Slot::Empty(Legality::Legal))
+            .copied()
+            .collect();
+
+        remaining.iter().all(|tile| {
+            self.grid.neighbouring_points(tile.0).into_iter().all(|pt| {
+                matches!(self.grid.get(pt), Slot::Empty(_)) && !remaining.contains(&Tile(pt))
+            })
+        })
+    }
+
     pub fn is_terminated(&self) -> bool {
         self.terminated
     }
@@ -433,26 +600,43 @@ impl Acquire {
     }
 
     fn provide_final_bonuses(&mut self) {
-        for chain in &CHAIN_ARRAY {
-            self.provide_bonuses(*chain);
+        for (player_id, bonus) in self.projected_final_bonuses() {
+            let player = self.get_player_by_id_mut(player_id);
+            player.money += bonus;
+            player.stats.bonuses_received += bonus;
         }
     }
 
+    /// Moves on to the next player who has a tile they can place, skipping everyone who has to
+    /// pass. The game ends if the turn limit is hit or nobody can play for two rounds.
     fn move_to_next_player_who_can_play_a_tile(&mut self) {
-        let mut count = 0;
+        let mut num_passes = 0;
         loop {
             self.phase = Phase::AwaitingTilePlacement;
             self.go_next_turn();
 
-            if self.player_has_any_valid_tiles(self.current_player_id) {
+            if self.options.max_turns.is_some_and(|max_turns| self.turn >= max_turns) {
+                self.terminated = true;
+                self.provide_final_bonuses();
+                break;
+            }
+
+            if self.options.auto_discard_dead {
+                self.discard_dead_tiles(self.current_player_id);
+            }
+
+            if !self.player_must_pass(self.current_player_id) {
                 break;
             }
 
-            self.player_trade_in_illegal_tiles(self.current_player_id);
+            // the player's turn is skipped, but they still get to trade in whatever can never
+            // be played so they might have something next time around
+            self.discard_dead_tiles(self.current_player_id);
 
-            count += 1;
+            num_passes += 1;
 
-            if count == self.players.len() * 2 {
+            // nobody has been able to play for two whole rounds
+            if num_passes == self.players.len() * 2 {
                 self.terminated = true;
                 self.provide_final_bonuses();
                 break;
@@ -461,10 +645,99 @@ impl Acquire {
     }
 
     fn may_terminate(&self) -> bool {
-        self.grid.all_chains_are_safe() || self.grid.game_ending_chain_exists()
+        self.termination_eligibility().is_some()
+    }
+
+    /// Why the game may be ended right now, or None if it can't be. A chain reaching the
+    /// game ending size is reported ahead of every chain being safe.
+    pub fn termination_eligibility(&self) -> Option<TerminationCause> {
+        if let Some((chain, size)) = self.grid.largest_chain() {
+            if size >= self.grid.game_ending_chain_size {
+                return Some(TerminationCause::ChainReachedEndSize(chain));
+            }
+        }
+
+        if self.grid.all_chains_are_safe() {
+            return Some(TerminationCause::AllChainsSafe);
+        }
+
+        None
+    }
+
+    /// How far along the board is towards each of the ways the game can be ended.
+    pub fn termination_progress(&self) -> TerminationProgress {
+        TerminationProgress {
+            largest_chain_size: self.grid.largest_chain().map_or(0, |(_, size)| size),
+            game_ending_size: self.grid.game_ending_chain_size,
+            safe_chain_count: self.grid.active_chains()
+                .iter()
+                .filter(|chain| self.grid.chain_size(**chain) >= self.grid.safe_chain_size)
+                .count() as u8,
+            total_chain_count: self.grid.num_chains,
+        }
+    }
+
+    pub fn board_stats(&self) -> BoardStats {
+        self.grid.board_stats()
+    }
+
+    /// What would happen if the tile were placed right now, worked out on a copy of the board.
+    pub fn placement_outcome(&self, tile: Tile) -> PlacementOutcome {
+        if self.grid.is_pt_out_of_bounds(tile.0) || !matches!(self.grid.get(tile.0), Slot::Empty(_)) {
+            return PlacementOutcome::Illegal { permanent: true };
+        }
+
+        match self.grid.clone_without_indicators().place(tile) {
+            PlaceTileResult::Proceed => PlacementOutcome::Proceed,
+            PlaceTileResult::SelectAvailableChain => PlacementOutcome::FoundChain,
+            PlaceTileResult::Merge { mergers } => PlacementOutcome::Merge { mergers },
+            PlaceTileResult::DecideTieBreak { tied_chains } => PlacementOutcome::Tiebreak { tied_chains },
+            PlaceTileResult::Illegal { allow_trade_in } => PlacementOutcome::Illegal { permanent: allow_trade_in },
+        }
+    }
+
+    /// Every tile in the player's hand along with what placing it would do, in hand order.
+    pub fn classify_hand(&self, player_id: PlayerId) -> Vec<(Tile, TileCategory)> {
+        self.get_player_by_id(player_id).tiles.iter().map(|tile| {
+            let category = match self.placement_outcome(*tile) {
+                PlacementOutcome::Proceed => {
+                    let neighbours = self.grid.neighbours(tile.0);
+                    if self.grid.chains_in_slots(&neighbours).is_empty() {
+                        TileCategory::Neutral
+                    } else {
+                        TileCategory::Grower
+                    }
+                }
+                PlacementOutcome::FoundChain => TileCategory::Founder,
+                PlacementOutcome::Merge { .. } |
+                PlacementOutcome::Tiebreak { .. } => TileCategory::Merger,
+                PlacementOutcome::Illegal { permanent: true } => TileCategory::Dead,
+                PlacementOutcome::Illegal { permanent: false } => TileCategory::TempBlocked,
+            };
+
+            (*tile, category)
+        }).collect()
+    }
+
+    /// The tiles in the player's hand which would leave a chain at the game ending size, by
+    /// founding, growing or merging into one that large.
+    pub fn game_ending_tiles(&self, player_id: PlayerId) -> Vec<Tile> {
+        self.get_player_by_id(player_id)
+            .tiles
+            .iter()
+            .filter(|tile| self.grid.get(tile.0) == Slot::Empty(Legality::Legal))
+            .filter(|tile| self.grid.size_after_placing(tile.0) >= self.grid.game_ending_chain_size)
+            .copied()
+            .collect()
+    }
+
+    /// The player has no tile they can place, every one of them either merges safe chains or
+    /// would found a chain while none are available. Their turn is skipped when it comes up.
+    pub fn player_must_pass(&self, player_id: PlayerId) -> bool {
+        !self.player_has_any_valid_tiles(player_id)
     }
 
-    fn player_has_any_valid_tiles(&mut self, player_id: PlayerId) -> bool {
+    fn player_has_any_valid_tiles(&self, player_id: PlayerId) -> bool {
         let player = self.get_player_by_id(player_id);
         player.tiles.iter().any(|tile| {
             match self.grid.get(tile.0) {
@@ -480,12 +753,50 @@ impl Acquire {
         })
     }
 
+    /// Moves a merge on to its next defunct chain. Its bonuses are paid out from the holdings as
+    /// they are right now, then the first holder from the current player on decides what to do
+    /// with their stock. Chains nobody holds are absorbed without any decisions, and once there
+    /// are no defunct chains left the player goes on to buy stock.
+    fn start_next_merger(&mut self, surviving_chain: Chain) {
+        let Phase::Merge { mergers_remaining, .. } = &mut self.phase else {
+            panic!("can only start a merger during a merge");
+        };
+
+        let mut mergers = std::mem::take(mergers_remaining);
+        mergers.retain(|merger| self.num_players_with_stock_in_chain(merger.defunct_chain) > 0);
+
+        if mergers.is_empty() {
+            let pt = self.grid.previously_placed_tile_pt.expect("a previously placed tile");
+            self.grid.fill_chain(pt, surviving_chain);
+            self.phase = Phase::AwaitingStockPurchase;
+            return;
+        }
+
+        let defunct_chain = mergers[0].defunct_chain;
+        mergers[0].num_remaining_players_to_merge = Some(self.num_players_with_stock_in_chain(defunct_chain));
+
+        let merging_player_id = self.player_ids_in_order(self.current_player_id)
+            .into_iter()
+            .find(|player_id| self.get_player_by_id(*player_id).stocks.has_any(defunct_chain))
+            .unwrap();
+
+        self.provide_bonuses(defunct_chain);
+
+        self.phase = Phase::Merge {
+            merging_player_id,
+            phase: MergePhase::AwaitingMergeDecision,
+            mergers_remaining: mergers,
+        };
+    }
+
     fn provide_bonuses(&mut self, chain: Chain) {
         let bonuses = self.chain_bonus(chain);
         for (player_id, bonus) in bonuses {
             #[cfg(test)]
             println!("Player {} received a bonus of ${bonus}", player_id.0);
-            self.get_player_by_id_mut(player_id).money += bonus;
+            let player = self.get_player_by_id_mut(player_id);
+            player.money += bonus;
+            player.stats.bonuses_received += bonus;
         }
     }
 
@@ -497,15 +808,88 @@ impl Acquire {
         }
     }
 
-    fn player_trade_in_illegal_tiles(&mut self, player_id: PlayerId) {
+    /// The tile the next call to `player_take_tile` will draw. This is hidden information, so
+    /// it's only available for writing tests.
+    #[cfg(feature = "testing")]
+    pub fn peek_next_draw(&self) -> Option<Tile> {
+        self.tiles.last().copied()
+    }
+
+    /// Counts the distinct states reachable in at most `depth` actions, not counting this one.
+    /// States are compared by their snapshot bytes. Only practical on tiny boards, for checking
+    /// phase transitions exhaustively.
+    #[cfg(feature = "testing")]
+    pub fn enumerate_descendants(&self, depth: usize) -> usize {
+        let mut seen: ahash::HashSet<Vec<u8>> = Default::default();
+        let mut frontier = vec![self.lightweight_clone()];
+
+        for _ in 0..depth {
+            let mut next = vec![];
+
+            for state in &frontier {
+                if state.terminated {
+                    continue;
+                }
+
+                for action in state.actions() {
+                    let child = state.apply_action(action);
+                    if seen.insert(child.to_bytes()) {
+                        next.push(child);
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        seen.len()
+    }
+
+    #[cfg(feature = "testing")]
+    pub fn set_player_money(&mut self, player_id: PlayerId, money: u32) {
+        self.get_player_by_id_mut(player_id).money = money;
+    }
+
+    /// Moves shares from the bank to the player, so the total number of shares stays the same.
+    /// Panics if the bank doesn't have enough.
+    #[cfg(feature = "testing")]
+    pub fn give_player_stock(&mut self, player_id: PlayerId, chain: Chain, amount: u8) {
+        self.stocks.withdraw(chain, amount).expect("enough stock in the bank");
+        self.get_player_by_id_mut(player_id).stocks.deposit(chain, amount);
+    }
+
+    /// Replaces the player's hand. The tiles aren't taken out of the bag or anyone else's hand.
+    #[cfg(feature = "testing")]
+    pub fn set_player_hand(&mut self, player_id: PlayerId, tiles: Vec<Tile>) {
+        self.get_player_by_id_mut(player_id).tiles = tiles;
+    }
+
+    /// Makes the next draws come out in the order of `tiles`, after which the rest of the bag is
+    /// drawn as it was shuffled. The queued tiles are moved to the draw end of the bag rather
+    /// than kept separately, so tile counts and snapshots stay correct. Panics if one of the
+    /// tiles isn't in the bag.
+    #[cfg(feature = "testing")]
+    pub fn set_draw_queue(&mut self, tiles: VecDeque<Tile>) {
+        for tile in &tiles {
+            let idx = self.tiles.iter().position(|t| t == tile).unwrap_or_else(|| panic!("{tile} is not in the bag"));
+            self.tiles.remove(idx);
+        }
+
+        self.tiles.extend(tiles.into_iter().rev());
+    }
+
+    /// Trades in every tile in the player's hand that can never be played, drawing
+    /// replacements from the bag. Returns the tiles that were discarded.
+    pub fn discard_dead_tiles(&mut self, player_id: PlayerId) -> Vec<Tile> {
         let grid = self.grid.clone();
         let num_remaining_tiles = self.tiles.len();
+        let hand_size = self.options.num_tiles as usize;
 
-        let tiles_to_draw = {
+        let (discarded, tiles_to_draw) = {
             let player = self.get_player_by_id_mut(player_id);
-            player.tiles = player.tiles
+            let (kept, discarded): (Vec<Tile>, Vec<Tile>) = player.tiles
                 .iter()
-                .filter(|tile| {
+                .partition(|tile| {
                     match grid.get(tile.0) {
                         Slot::Empty(legality) => {
                             match legality {
@@ -525,17 +909,17 @@ impl Acquire {
                         }
                     }
 
-                })
-                .copied()
-                .collect();
+                });
+
+            player.tiles = kept;
 
-            let required_tiles: usize = 6 - player.tiles.len();
-            required_tiles.min(num_remaining_tiles)
+            let required_tiles: usize = hand_size.saturating_sub(player.tiles.len());
+            (discarded, required_tiles.min(num_remaining_tiles))
         };
 
         #[cfg(test)]
-        if tiles_to_draw > 0 {
-            println!("Player {} replaces {} of their illegal tiles.", player_id.0, tiles_to_draw);
+        if !discarded.is_empty() {
+            println!("Player {} replaces {} of their illegal tiles.", player_id.0, discarded.len());
         }
 
         // have to do some weird shit in here to deal with interior mutability
@@ -544,11 +928,18 @@ impl Acquire {
             let player = self.get_player_by_id_mut(player_id);
             player.tiles.push(tile);
         }
+
+        discarded
     }
 
     fn go_next_turn(&mut self) {
         self.current_player_id = self.next_player_id();
         self.turn += 1;
+        self.grid.indicators.clear();
+    }
+
+    pub fn player_stats(&self, player_id: PlayerId) -> PlayerStats {
+        self.get_player_by_id(player_id).stats
     }
 
     pub fn get_player_by_id(&self, player_id: PlayerId) -> &Player {
@@ -559,6 +950,28 @@ impl Acquire {
         &mut self.players[player_id.0 as usize]
     }
 
+    /// Reseats the players so that `order[n]` becomes `PlayerId(n)`, keeping their hands, money
+    /// and stock. Whoever's decision it was still has it. Panics if `order` isn't every player
+    /// exactly once.
+    pub fn set_player_order(&mut self, order: &[PlayerId]) {
+        assert!(
+            order.len() == self.players.len() && order.iter().all_unique() && order.iter().all(|id| (id.0 as usize) < self.players.len()),
+            "{order:?} is not an ordering of every player"
+        );
+
+        let reseat = |id: PlayerId| PlayerId(order.iter().position(|other| *other == id).unwrap() as u8);
+
+        self.players = order.iter().map(|id| self.players[id.0 as usize].clone()).collect();
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.id = PlayerId(seat as u8);
+        }
+
+        self.current_player_id = reseat(self.current_player_id);
+        if let Phase::Merge { ref mut merging_player_id, .. } = self.phase {
+            *merging_player_id = reseat(*merging_player_id);
+        }
+    }
+
     fn next_player_id(&self) -> PlayerId {
         PlayerId((self.current_player_id.0 + 1) % self.players.len() as u8)
     }
@@ -588,6 +1001,95 @@ impl Acquire {
         }
     }
 
+    /// Backs out of the merge in progress, giving the game as it was just before the merging tile
+    /// was placed. Everything since is undone with it, including any bonuses already paid and
+    /// decisions already made. None outside of a merge, and also for games resumed from a
+    /// snapshot or lightweight clone mid-merge since neither keeps the earlier state.
+    ///
+    /// Keeping that state around costs an extra clone of the game, minus its log, every time a
+    /// tile sets off a merge.
+    pub fn cancel_merge(&self) -> Option<Acquire> {
+        let Phase::Merge { .. } = self.phase else {
+            return None;
+        };
+
+        let mut game = self.pre_merge.as_deref()?.clone();
+        game.log = self.log.as_ref().map(|log| {
+            log.iter().filter(|entry| entry.step < game.step).cloned().collect()
+        });
+
+        Some(game)
+    }
+
+    /// The merger players are currently deciding on, None outside of a merge or while the
+    /// surviving chain has yet to be picked.
+    pub fn current_merger(&self) -> Option<MergingChains> {
+        match &self.phase {
+            Phase::Merge { mergers_remaining, .. } => mergers_remaining.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Every chain the merge in progress is going to absorb, largest first, including the ones
+    /// nobody holds stock in and so never get a merger decision. While the tiebreak is still
+    /// being decided this is only the smaller chains, since any of the tied chains could survive.
+    pub fn pending_merge_chains(&self) -> Vec<Chain> {
+        let Phase::Merge { phase, mergers_remaining, .. } = &self.phase else {
+            return vec![];
+        };
+        let Some(pt) = self.grid.previously_placed_tile_pt else {
+            return vec![];
+        };
+
+        // the defunct chains stay on the board until the whole merge is over
+        let neighbouring_chains = self.grid.chains_in_slots(&self.grid.neighbours(pt));
+        let mut chains: Vec<Chain> = match phase {
+            MergePhase::AwaitingTiebreakSelection { tied_chains } => {
+                neighbouring_chains.iter().filter(|chain| !tied_chains.contains(chain)).copied().collect()
+            }
+            MergePhase::AwaitingMergeDecision => {
+                let surviving_chain = mergers_remaining[0].merging_chain;
+                neighbouring_chains.iter().filter(|chain| **chain != surviving_chain).copied().collect()
+            }
+        };
+
+        self.grid.sort_defunct_chains(&mut chains);
+        chains
+    }
+
+    /// The surviving chain and how big it'll be once the merge is over, with every defunct chain,
+    /// the merging tile and any unclaimed tiles it connects absorbed. None outside of a merge or
+    /// while the surviving chain has yet to be picked.
+    pub fn projected_survivor_size(&self) -> Option<(Chain, u16)> {
+        let merger = self.current_merger()?;
+        let pt = self.grid.previously_placed_tile_pt?;
+
+        Some((merger.merging_chain, self.grid.size_after_placing(pt)))
+    }
+
+    /// Everyone who still has to decide what to do with their stock in the chain currently being
+    /// merged, starting with the player deciding now. Empty outside of a merge decision.
+    pub fn merge_turn_order(&self) -> Vec<PlayerId> {
+        let Phase::Merge { merging_player_id, phase: MergePhase::AwaitingMergeDecision, ref mergers_remaining } = self.phase else {
+            return vec![];
+        };
+
+        let defunct_chain = mergers_remaining[0].defunct_chain;
+
+        // holders decide in seat order starting from whoever's turn it is, so everyone between
+        // them and the merging player has already had their say
+        let mut order = vec![merging_player_id];
+        order.extend(
+            self.player_ids_in_order(merging_player_id)
+                .into_iter()
+                .skip(1)
+                .take_while(|player_id| *player_id != self.current_player_id)
+                .filter(|player_id| self.get_player_by_id(*player_id).stocks.has_any(defunct_chain))
+        );
+
+        order
+    }
+
     fn player_ids_in_order(&self, starting_player_id: PlayerId) -> Vec<PlayerId> {
         (0..self.players.len() as u8).map(|n| {
             PlayerId((starting_player_id.0 + n) % self.players.len() as u8)
@@ -595,12 +1097,15 @@ impl Acquire {
     }
 
 
-    fn purchasable_combinations(&self, purchasing_player_id: PlayerId) -> Vec<[BuyOption; 3]> {
+    /// Buying nothing, which is always one of the actions while awaiting a stock purchase.
+    pub fn pass_purchase_action(&self) -> Action {
+        Action::PurchaseStock(self.current_player_id, vec![BuyOption::None; self.options.max_buys_per_turn as usize])
+    }
+
+    fn purchasable_combinations(&self, purchasing_player_id: PlayerId) -> Vec<Vec<BuyOption>> {
         let player = self.get_player_by_id(purchasing_player_id);
         let remaining_money = player.money;
 
-        let mut combinations = vec![];
-
         let buy_options = {
             let mut buy_option_chains: Vec<BuyOption> = self.grid.existing_chains()
                 .iter()
@@ -621,7 +1126,7 @@ impl Acquire {
         // this anonymous function is reused to
         // simulate purchasing each stock to determine if it's
         // possible to purchase the combination of stocks at all
-        let can_buy = |buy_options: &[BuyOption; 3]| -> bool {
+        let can_buy = |buy_options: &[BuyOption]| -> bool {
             let mut money = remaining_money;
             let mut stock = self.stocks.clone();
 
@@ -647,24 +1152,39 @@ impl Acquire {
             true
         };
 
-        let num_buy_options = buy_options.len();
-        for i in 0..num_buy_options {
-            for j in i..num_buy_options {
-                for k in j..num_buy_options {
-                    let combination = [
-                        buy_options[i],
-                        buy_options[j],
-                        buy_options[k]
-                    ];
-
-                    if can_buy(&combination) {
-                        combinations.push(combination);
-                    }
-                }
-            }
-        }
+        // every multiset of `max_buys_per_turn` options, the None option pads out
+        // combinations where the player buys fewer than the max
+        buy_options
+            .into_iter()
+            .combinations_with_replacement(self.options.max_buys_per_turn as usize)
+            .filter(|combination| can_buy(combination))
+            .collect()
+    }
 
-        combinations
+    /// The limits on the current merge decision, None unless someone is deciding on a merger.
+    pub fn merge_constraints(&self) -> Option<MergeConstraints> {
+        let Phase::Merge { merging_player_id, phase: MergePhase::AwaitingMergeDecision, .. } = self.phase else {
+            return None;
+        };
+
+        let merging_chains = self.current_merger()?;
+        let held = self.get_player_by_id(merging_player_id).stocks.amount(merging_chains.defunct_chain);
+        let bank = self.stocks.amount(merging_chains.merging_chain);
+
+        let (max_trade_in, trade_in_limit) = if bank < held / 2 {
+            (bank * 2, TradeInLimit::BankStock)
+        } else {
+            (held / 2 * 2, TradeInLimit::Holdings)
+        };
+
+        Some(MergeConstraints {
+            merging_player_id,
+            merging_chains,
+            held,
+            max_sell: held,
+            max_trade_in,
+            trade_in_limit,
+        })
     }
 
     fn merge_combinations(&self, merging_player_id: PlayerId, merging_chains: MergingChains) -> Vec<MergeDecision> {
@@ -697,9 +1217,10 @@ impl Acquire {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     PlaceTile(PlayerId, Tile),
-    PurchaseStock(PlayerId, [BuyOption; 3]),
+    PurchaseStock(PlayerId, Vec<BuyOption>),
     SelectChainToCreate(PlayerId, Chain),
     SelectChainForTiebreak(PlayerId, Chain),
     DecideMerge {
@@ -709,6 +1230,20 @@ pub enum Action {
     Terminate(PlayerId, bool),
 }
 
+impl Action {
+    /// The player taking the action, which during a merge may not be the player whose turn it is.
+    pub fn player_id(&self) -> PlayerId {
+        match self {
+            Action::PlaceTile(player_id, _) |
+            Action::PurchaseStock(player_id, _) |
+            Action::SelectChainToCreate(player_id, _) |
+            Action::SelectChainForTiebreak(player_id, _) |
+            Action::Terminate(player_id, _) => *player_id,
+            Action::DecideMerge { merging_player_id, .. } => *merging_player_id,
+        }
+    }
+}
+
 #[allow(unused_must_use)]
 impl Display for Action {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -784,6 +1319,7 @@ impl Display for Action {
 }
 
 #[derive(Copy, Clone, Debug,  Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MergeDecision {
     merging_chains: MergingChains,
     sell: u8,
@@ -791,8 +1327,9 @@ pub struct MergeDecision {
     // 'keep' is the fallback
 }
 
-#[derive(Debug, Clone)]
-enum Phase {
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
     AwaitingTilePlacement,
     AwaitingChainCreationSelection,
     AwaitingStockPurchase,
@@ -804,21 +1341,112 @@ enum Phase {
     },
 }
 
-#[derive(Clone, Debug)]
-enum MergePhase {
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MergePhase {
     AwaitingTiebreakSelection {
         tied_chains: Vec<Chain>
     },
     AwaitingMergeDecision,
 }
 
+/// What's holding back the number of defunct shares a player can trade in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TradeInLimit {
+    /// two for one, so an odd share can't be traded in
+    Holdings,
+    /// the bank has run out of the surviving chain's stock
+    BankStock,
+}
+
+/// See `Acquire::placement_outcome`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlacementOutcome {
+    /// the tile stands alone or grows a single chain
+    Proceed,
+    /// the tile joins unclaimed tiles, and the player picks which chain it becomes
+    FoundChain,
+    /// the surviving chain is already decided, mergers are listed in the order they happen
+    Merge {
+        mergers: Vec<MergingChains>,
+    },
+    /// the largest chains are tied, so the player picks the survivor first
+    Tiebreak {
+        tied_chains: Vec<Chain>,
+    },
+    /// permanently illegal tiles can be traded in, temporarily illegal ones have to be kept
+    Illegal {
+        permanent: bool,
+    },
+}
+
+/// See `Acquire::classify_hand`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TileCategory {
+    Founder,
+    Grower,
+    Merger,
+    Dead,
+    /// would found a chain, but every chain is already on the board
+    TempBlocked,
+    /// touches nothing
+    Neutral,
+}
+
+/// See `Acquire::merge_constraints`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MergeConstraints {
+    pub merging_player_id: PlayerId,
+    pub merging_chains: MergingChains,
+    /// shares of the defunct chain the player has to dispose of or keep
+    pub held: u8,
+    pub max_sell: u8,
+    /// in defunct shares given up, always even
+    pub max_trade_in: u8,
+    pub trade_in_limit: TradeInLimit,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TerminationCause {
+    AllChainsSafe,
+    ChainReachedEndSize(Chain),
+}
+
+/// See `Acquire::termination_progress`. The game can be ended once the largest chain reaches
+/// the game ending size, or once every chain is safe.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TerminationProgress {
+    pub largest_chain_size: u16,
+    pub game_ending_size: u16,
+    pub safe_chain_count: u8,
+    /// every chain that can be founded, whether or not it's on the board
+    pub total_chain_count: u8,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct MergingChains {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergingChains {
     merging_chain: Chain,
     defunct_chain: Chain,
     num_remaining_players_to_merge: Option<u8>,
 }
 
+impl MergingChains {
+    /// The chain which survives the merge.
+    pub fn merging_chain(&self) -> Chain {
+        self.merging_chain
+    }
+
+    pub fn defunct_chain(&self) -> Chain {
+        self.defunct_chain
+    }
+
+    /// How many of the defunct chain's holders have yet to decide, once the merger has started.
+    pub fn num_remaining_players_to_merge(&self) -> Option<u8> {
+        self.num_remaining_players_to_merge
+    }
+}
+
 #[allow(unused_must_use)]
 impl Display for Acquire {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -865,7 +1493,8 @@ impl Display for Acquire {
             writeln!(f);
         }
 
-        f.write_fmt(format_args!("{}", self.grid));
+        let render_options = RenderOptions { coordinate_style: self.options.coordinate_style, ..Default::default() };
+        f.write_str(&self.grid.render(&render_options));
 
         Ok(())
     }
@@ -873,6 +1502,7 @@ impl Display for Acquire {
 
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerId(pub u8);
 
 impl Debug for PlayerId {
@@ -882,6 +1512,7 @@ impl Debug for PlayerId {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BuyOption {
     None,
     Chain(Chain),
@@ -889,25 +1520,775 @@ pub enum BuyOption {
 
 #[cfg(test)]
 mod test {
+    use ahash::HashSet;
     use rand::SeedableRng;
     use rand::seq::SliceRandom;
-    use crate::{Acquire, Options, Phase, PlayerId, tile};
-    use crate::chain::Chain;
-    use crate::grid::Slot;
+    use crate::{Acquire, AcquireError, Action, BuyOption, CoordinateStyle, Options, PlacementOutcome, TerminationProgress, TileCategory, PlayerStats, StockSupply, Phase, PlayerId, Point, TerminationCause, Tile, TradeInLimit, tile};
+    use crate::chain::CHAIN_ARRAY;
+    use crate::chain::{Chain, ChainTable};
+    use crate::grid::{Legality, Slot};
 
     fn game_test_instance() -> Acquire {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        Acquire::new(rng, &Options::default())
+        Acquire::from_seed(2, &Options::default())
+    }
+
+    #[test]
+    fn test_from_seed() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let game = Acquire::new(&mut rng, &Options::default());
+
+        assert_eq!(Acquire::from_seed(2, &Options::default()).to_bytes(), game.to_bytes());
+        assert_ne!(Acquire::from_seed(3, &Options::default()).to_bytes(), game.to_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_peek_next_draw() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.phase = Phase::AwaitingStockPurchase;
+
+        let next_draw = game.peek_next_draw().unwrap();
+        assert_eq!(game.peek_next_draw(), Some(next_draw));
+
+        let game = game.apply_action(Action::PurchaseStock(PlayerId(0), vec![BuyOption::None; 3]));
+        assert_eq!(game.players[0].tiles.last(), Some(&next_draw));
+        assert_ne!(game.peek_next_draw(), Some(next_draw));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_enumerate_descendants() {
+        let options = Options { num_players: 2, num_tiles: 2, grid_width: 3, grid_height: 3, ..Options::default() };
+        let game = Acquire::from_seed(2, &options);
+
+        // a lone first tile can't do anything but pass the turn, so each tile in player 0's
+        // hand is one state, then each of player 1's tiles after that
+        assert_eq!(game.enumerate_descendants(0), 0);
+        assert_eq!(game.enumerate_descendants(1), 2);
+        assert_eq!(game.enumerate_descendants(2), 2 + 2 * 2);
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_scenario_setters() {
+        // the same setup as test_four_way_merge_with_stakes
+        let mut game = game_test_instance();
+
+        for (a, b, chain) in [("D1", "D2", Chain::American), ("D4", "D5", Chain::Festival), ("B3", "C3", Chain::Continental), ("E3", "F3", Chain::Tower)] {
+            game.grid.place(a.try_into().unwrap());
+            game.grid.place(b.try_into().unwrap());
+            game.grid.fill_chain(b.try_into().unwrap(), chain);
+        }
+
+        let stakes = [[3, 3, 3, 3], [1, 2, 3, 4], [5, 3, 2, 0], [8, 0, 2, 1]];
+        for (id, stake) in stakes.iter().enumerate() {
+            for (chain, amount) in [Chain::Festival, Chain::American, Chain::Continental, Chain::Tower].into_iter().zip(stake) {
+                game.give_player_stock(PlayerId(id as u8), chain, *amount);
+            }
+        }
+
+        game.set_player_hand(PlayerId(0), vec![tile!("D3")]);
+        game.set_player_money(PlayerId(3), 0);
+
+        assert_eq!(game.stocks.amount(Chain::Festival), 25 - 17);
+
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D3")));
+        assert_eq!(game.actions().len(), 4);
+
+        game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.players[0].money, 7500);
+        assert_eq!(game.players[3].money, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_set_draw_queue() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        let queue = [game.tiles[0], game.tiles[10], game.tiles[20]];
+        let num_tiles_in_bag = game.tiles.len();
+        game.set_draw_queue(queue.into_iter().collect());
+        assert_eq!(game.tiles.len(), num_tiles_in_bag);
+
+        // each player draws once their purchase is over
+        for (idx, tile) in queue.iter().enumerate() {
+            let player_id = PlayerId(idx as u8);
+            game.phase = Phase::AwaitingStockPurchase;
+            game.current_player_id = player_id;
+            game = game.apply_action(Action::PurchaseStock(player_id, vec![BuyOption::None; 3]));
+
+            assert_eq!(game.players[idx].tiles.last(), Some(tile));
+        }
+    }
+
+    #[test]
+    fn test_starting_player() {
+        let options = Options { starting_player: Some(PlayerId(2)), ..Options::default() };
+        let mut game = Acquire::from_seed(2, &options);
+
+        // the deal doesn't depend on who starts
+        assert_eq!(game.players[0].tiles, game_test_instance().players[0].tiles);
+
+        for expected in [2, 3, 0, 1, 2] {
+            assert_eq!(game.current_player_id, PlayerId(expected));
+            game = game.play_turn(|_, actions| actions[0].clone());
+        }
+
+        // left to chance, the same seed always picks the same player
+        let options = Options { starting_player: None, ..Options::default() };
+        let starting_player = Acquire::from_seed(2, &options).current_player_id;
+        assert_eq!(Acquire::from_seed(2, &options).current_player_id, starting_player);
+        assert!(starting_player.0 < 4);
+    }
+
+    #[test]
+    fn test_set_player_order() {
+        let mut game = game_test_instance();
+        let original = game.clone();
+
+        game.set_player_order(&[PlayerId(2), PlayerId(0), PlayerId(3), PlayerId(1)]);
+
+        assert_eq!(game.players[0].tiles, original.players[2].tiles);
+        assert_eq!(game.players[3].tiles, original.players[1].tiles);
+        assert!(game.players.iter().enumerate().all(|(seat, player)| player.id == PlayerId(seat as u8)));
+
+        // player 0 was about to play and still is, now from seat 1
+        assert_eq!(game.current_player_id, PlayerId(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_player_order_invalid() {
+        let mut game = game_test_instance();
+        game.set_player_order(&[PlayerId(0), PlayerId(0), PlayerId(1), PlayerId(2)]);
+    }
+
+    /// Tower A1-A3 and American A5-A6, with player 0 about to merge them with A4.
+    fn purchase_after_merge_instance() -> Acquire {
+        let mut game = game_test_instance();
+        for tile in [tile!("A1"), tile!("A2"), tile!("A3"), tile!("A5"), tile!("A6")] {
+            game.grid.place(tile);
+        }
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.grid.fill_chain(tile!("A5"), Chain::American);
+
+        game.players[0].tiles[0] = tile!("A4");
+        game
+    }
+
+    #[test]
+    fn test_purchase_after_merge() {
+        let assert_only_survivor_purchasable = |game: &Acquire| {
+            assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+            assert_eq!(game.grid.chain_size(Chain::American), 0);
+            assert_eq!(game.grid.chain_size(Chain::Tower), 6);
+            assert_eq!(game.grid.get(tile!("A4")), Slot::Chain(Chain::Tower));
+
+            for buys in game.purchasable_combinations(PlayerId(0)) {
+                assert!(buys.iter().all(|buy| matches!(buy, BuyOption::None | BuyOption::Chain(Chain::Tower))), "{:?}", buys);
+            }
+        };
+
+        // with stakes in the defunct chain the merge waits on decisions first
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 2);
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        game = game.apply_action(game.actions().remove(0));
+        assert_only_survivor_purchasable(&game);
+
+        // nobody holds american, so the merge completes straight away
+        let game = purchase_after_merge_instance().apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert_only_survivor_purchasable(&game);
+    }
+
+    #[test]
+    fn test_projected_survivor_size() {
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 2);
+        // an unclaimed tile hanging off the merging tile gets pulled in too
+        game.grid.place(tile!("B4"));
+        assert_eq!(game.projected_survivor_size(), None);
+
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert_eq!(game.projected_survivor_size(), Some((Chain::Tower, 7)));
+
+        let game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+        assert_eq!(game.grid.chain_size(Chain::Tower), 7);
+        assert_eq!(game.projected_survivor_size(), None);
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 2);
+        let money_before_merge = game.players[1].money;
+
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        let game = game.apply_action(game.actions().remove(0));
+        let buys = vec![BuyOption::Chain(Chain::Tower), BuyOption::Chain(Chain::Tower), BuyOption::None];
+        let game = game.apply_action(Action::PurchaseStock(PlayerId(0), buys));
+
+        assert_eq!(game.player_stats(PlayerId(0)), PlayerStats {
+            tiles_placed: 1,
+            shares_bought: 2,
+            mergers_initiated: 1,
+            bonuses_received: 0,
+        });
+
+        // player 1 kept their american, so the bonus is all the money they made
+        let stats = game.player_stats(PlayerId(1));
+        assert_eq!(stats.tiles_placed, 0);
+        assert!(stats.bonuses_received > 0);
+        assert_eq!(stats.bonuses_received, game.players[1].money - money_before_merge);
+
+        let decoded = Acquire::from_bytes(&game.to_bytes()).unwrap();
+        assert_eq!(decoded.player_stats(PlayerId(0)), game.player_stats(PlayerId(0)));
+    }
+
+    #[test]
+    fn test_cancel_merge() {
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 2);
+        game.players[2].stocks.deposit(Chain::American, 1);
+        let before = game.to_bytes();
+        assert!(game.cancel_merge().is_none());
+
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        let cancelled = game.cancel_merge().unwrap();
+        assert_eq!(cancelled.to_bytes(), before);
+
+        // after bonuses have been paid and player 1 has sold, it all goes back
+        let sell = game.actions().into_iter().find(|action| matches!(action, Action::DecideMerge { decision, .. } if decision.sell == 2)).unwrap();
+        let game = game.apply_action(sell);
+        assert!(matches!(game.phase, Phase::Merge { merging_player_id: PlayerId(2), .. }));
+
+        let cancelled = game.cancel_merge().unwrap();
+        assert_eq!(cancelled.to_bytes(), before);
+        assert_eq!(cancelled.phase, Phase::AwaitingTilePlacement);
+        assert!(game.lightweight_clone().cancel_merge().is_none());
+        assert_eq!(cancelled.grid.get(tile!("A4")), Slot::Empty(Legality::Legal));
+        assert!(cancelled.players[0].tiles.contains(&tile!("A4")));
+
+        // once the merge is over there's nothing to back out of
+        let game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+        assert!(game.cancel_merge().is_none());
+
+        // the log is cut back to where the game went back to rather than lost
+        let mut logged = purchase_after_merge_instance().with_logging();
+        logged.players[1].stocks.deposit(Chain::American, 2);
+        let logged = logged.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert_eq!(logged.log().len(), 1);
+        let cancelled = logged.cancel_merge().unwrap();
+        assert!(cancelled.log.is_some());
+        assert!(cancelled.log().is_empty());
+    }
+
+    #[test]
+    fn test_merge_turn_order() {
+        let mut game = purchase_after_merge_instance();
+        assert!(game.merge_turn_order().is_empty());
+
+        // player 0 is merging but has no stake in american
+        for player in &mut game.players[1..] {
+            player.stocks.deposit(Chain::American, 2);
+        }
+
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+
+        for expected in [vec![PlayerId(1), PlayerId(2), PlayerId(3)], vec![PlayerId(2), PlayerId(3)], vec![PlayerId(3)]] {
+            let order = game.merge_turn_order();
+            assert_eq!(order, expected);
+            assert_eq!(order[0], bg_ai::State::current_player(&game));
+
+            game = game.apply_action(game.actions().remove(0));
+        }
+
+        assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+        assert!(game.merge_turn_order().is_empty());
+    }
+
+    #[test]
+    fn test_pass_purchase_action() {
+        let mut game = purchase_after_merge_instance();
+        game.phase = Phase::AwaitingStockPurchase;
+
+        let pass = game.pass_purchase_action();
+        assert_eq!(pass, Action::PurchaseStock(PlayerId(0), vec![BuyOption::None; 3]));
+
+        // player 0 can afford either chain, passing is still on offer
+        assert!(game.actions().len() > 1);
+        assert!(game.actions().contains(&pass));
+
+        let options = Options { max_buys_per_turn: 1, ..Options::default() };
+        let mut game = Acquire::from_seed(2, &options);
+        game.phase = Phase::AwaitingStockPurchase;
+        assert_eq!(game.actions(), vec![game.pass_purchase_action()]);
+    }
+
+    #[test]
+    fn test_bonuses_paid_as_each_merger_starts() {
+        let mut game = purchase_after_merge_instance();
+        game.grid.place(tile!("B4"));
+        game.grid.place(tile!("C4"));
+        game.grid.fill_chain(tile!("B4"), Chain::Festival);
+
+        // american and festival are both $300 a share, so $3000 majority and $1500 minority
+        game.players[1].stocks.deposit(Chain::American, 2);
+        game.players[1].stocks.deposit(Chain::Festival, 3);
+        game.players[2].stocks.deposit(Chain::American, 1);
+        game.players[2].stocks.deposit(Chain::Festival, 1);
+
+        let decide = |game: &Acquire, sell: u8| -> Action {
+            game.actions().into_iter().find(|action| matches!(
+                action, Action::DecideMerge { decision, .. } if decision.sell == sell && decision.trade_in == 0
+            )).unwrap()
+        };
+
+        // american goes first, only its bonuses are paid
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert_eq!(game.players[1].money, 6000 + 3000);
+        assert_eq!(game.players[2].money, 6000 + 1500);
+        assert_eq!(game.merge_turn_order(), vec![PlayerId(1), PlayerId(2)]);
+
+        // player 1 sells all their american, which leaves their festival stake alone
+        game = game.apply_action(decide(&game, 2));
+        assert_eq!(game.players[1].money, 6000 + 3000 + 600);
+        game = game.apply_action(decide(&game, 0));
+
+        // festival's bonuses come from the holdings as festival's merger starts
+        assert_eq!(game.players[1].money, 6000 + 3000 + 600 + 3000);
+        assert_eq!(game.players[2].money, 6000 + 1500 + 1500);
+        assert_eq!(game.merge_turn_order(), vec![PlayerId(1), PlayerId(2)]);
+
+        game = game.apply_action(decide(&game, 0));
+        game = game.apply_action(decide(&game, 0));
+        assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+        assert_eq!(game.grid.chain_size(Chain::Tower), 3 + 1 + 2 + 2);
+    }
+
+    #[test]
+    fn test_current_merger() {
+        let mut game = purchase_after_merge_instance();
+        assert_eq!(game.current_merger(), None);
+
+        game.players[1].stocks.deposit(Chain::American, 2);
+        game.players[2].stocks.deposit(Chain::American, 1);
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+
+        let merger = game.current_merger().unwrap();
+        assert_eq!(merger.merging_chain(), Chain::Tower);
+        assert_eq!(merger.defunct_chain(), Chain::American);
+        assert_eq!(merger.num_remaining_players_to_merge(), Some(2));
+
+        game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.current_merger().unwrap().num_remaining_players_to_merge(), Some(1));
+
+        game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.current_merger(), None);
+
+        // nothing to report until the tie is broken
+        assert_eq!(tied_merge_instance().current_merger(), None);
+    }
+
+    #[test]
+    fn test_active_player() {
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 5);
+        assert_eq!(game.active_player(), PlayerId(0));
+
+        // only player 1 holds the defunct chain, so it's their decision while it's still player 0's turn
+        let merging = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert!(matches!(merging.phase, Phase::Merge { .. }));
+        assert_eq!(merging.current_player_id, PlayerId(0));
+        assert_eq!(merging.active_player(), PlayerId(1));
+        assert!(merging.actions().iter().all(|action| action.player_id() == PlayerId(1)));
+    }
+
+    #[test]
+    fn test_merge_constraints() {
+        let mut game = purchase_after_merge_instance();
+        game.players[1].stocks.deposit(Chain::American, 5);
+        assert_eq!(game.merge_constraints(), None);
+
+        let merging = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        let constraints = merging.merge_constraints().unwrap();
+        assert_eq!(constraints.merging_player_id, PlayerId(1));
+        assert_eq!(constraints.held, 5);
+        assert_eq!(constraints.max_sell, 5);
+        assert_eq!(constraints.max_trade_in, 4);
+        assert_eq!(constraints.trade_in_limit, TradeInLimit::Holdings);
+
+        // with the bank out of tower, trading in is off the table
+        game.stocks.withdraw(Chain::Tower, 25).unwrap();
+        let merging = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        let constraints = merging.merge_constraints().unwrap();
+        assert_eq!(constraints.max_trade_in, 0);
+        assert_eq!(constraints.trade_in_limit, TradeInLimit::BankStock);
+
+        let actions = merging.actions();
+        assert_eq!(actions.len(), 6);
+        assert!(actions.iter().all(|action| matches!(action, Action::DecideMerge { decision, .. } if decision.trade_in == 0)));
+    }
+
+    #[test]
+    fn test_found_exhausted_chain() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+
+        // every share of tower is already out with another player
+        game.stocks.withdraw(Chain::Tower, 25).unwrap();
+        game.players[1].stocks.deposit(Chain::Tower, 25);
+
+        game.players[0].tiles[0] = tile!("A2");
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A2")));
+        assert_eq!(game.phase, Phase::AwaitingChainCreationSelection);
+
+        // the chain is still founded, there's just no free share to hand out
+        let game = game.apply_action(Action::SelectChainToCreate(PlayerId(0), Chain::Tower));
+        assert_eq!(game.phase, Phase::AwaitingStockPurchase);
+        assert_eq!(game.grid.chain_size(Chain::Tower), 2);
+        assert_eq!(game.players[0].stocks.amount(Chain::Tower), 0);
+        assert_eq!(game.stocks.amount(Chain::Tower), 0);
+        assert_eq!(game.actions(), vec![game.pass_purchase_action()]);
+    }
+
+    #[test]
+    fn test_always_show_purchase_phase() {
+        let game = game_test_instance();
+        let tile = game.players[0].tiles[0];
+
+        let next = game.apply_action(Action::PlaceTile(PlayerId(0), tile));
+        assert_eq!(next.phase, Phase::AwaitingTilePlacement);
+        assert_eq!(next.current_player_id, PlayerId(1));
+
+        let options = Options { always_show_purchase_phase: true, ..Options::default() };
+        let game = Acquire::from_seed(2, &options);
+
+        let next = game.apply_action(Action::PlaceTile(PlayerId(0), tile));
+        assert_eq!(next.phase, Phase::AwaitingStockPurchase);
+        assert_eq!(next.actions(), vec![next.pass_purchase_action()]);
+
+        let next = next.apply_action(next.pass_purchase_action());
+        assert_eq!(next.current_player_id, PlayerId(1));
+    }
+
+    #[test]
+    fn test_creatable_chains() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Tower);
+        game.grid.place(tile!("A1"));
+        game.players[0].tiles[0] = tile!("A2");
+
+        assert_eq!(game.creatable_chains(), Err(AcquireError::NotCreatingChain(Phase::AwaitingTilePlacement)));
+
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A2")));
+        assert_eq!(game.phase, Phase::AwaitingChainCreationSelection);
+
+        let chains = game.creatable_chains().unwrap();
+        assert_eq!(chains, game.grid.available_chains());
+        assert_eq!(chains.len(), 6);
+        assert!(!chains.contains(&Chain::Tower));
+    }
+
+    #[test]
+    fn test_placement_outcome() {
+        let mut game = game_test_instance();
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("A5"));
+        game.grid.place(tile!("A6"));
+        game.grid.fill_chain(tile!("A4"), Chain::American);
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Luxor);
+        game.grid.place(tile!("E1"));
+        game.grid.place(tile!("E3"));
+
+        let before = game.to_bytes();
+
+        assert_eq!(game.placement_outcome(tile!("G7")), PlacementOutcome::Proceed);
+        assert_eq!(game.placement_outcome(tile!("A7")), PlacementOutcome::Proceed);
+        assert_eq!(game.placement_outcome(tile!("E2")), PlacementOutcome::FoundChain);
+
+        let PlacementOutcome::Merge { mergers } = game.placement_outcome(tile!("A3")) else {
+            panic!("expected american to absorb tower");
+        };
+        assert_eq!(mergers.len(), 1);
+        assert_eq!((mergers[0].merging_chain, mergers[0].defunct_chain), (Chain::American, Chain::Tower));
+
+        let PlacementOutcome::Tiebreak { mut tied_chains } = game.placement_outcome(tile!("B1")) else {
+            panic!("expected tower and luxor to tie");
+        };
+        tied_chains.sort();
+        assert_eq!(tied_chains, vec![Chain::Tower, Chain::Luxor]);
+
+        assert_eq!(game.placement_outcome(tile!("A1")), PlacementOutcome::Illegal { permanent: true });
+
+        // nothing was placed for real
+        assert_eq!(game.to_bytes(), before);
+
+        // a tile between two safe chains is dead
+        let game = dead_tile_instance(Options::default());
+        assert_eq!(game.placement_outcome(tile!("A3")), PlacementOutcome::Illegal { permanent: true });
+    }
+
+    #[test]
+    fn test_classify_hand() {
+        let options = Options { safe_chain_size: 3, num_chains: 4, ..Options::default() };
+        let mut game = Acquire::from_seed(2, &options);
+        let chains = [("A1", "A3", Chain::Tower), ("A5", "A7", Chain::Luxor), ("C1", "C2", Chain::American)];
+        for (first, last, chain) in chains {
+            let first: Tile = first.try_into().unwrap();
+            let last: Tile = last.try_into().unwrap();
+            for x in first.0.x..=last.0.x {
+                game.grid.place(Tile::new(x, first.0.y));
+            }
+            game.grid.fill_chain(first.0, chain);
+        }
+        game.grid.place(tile!("E5"));
+
+        game.players[0].tiles = vec![tile!("E6"), tile!("A8"), tile!("B1"), tile!("A4"), tile!("G10")];
+        assert_eq!(game.classify_hand(PlayerId(0)), vec![
+            (tile!("E6"), TileCategory::Founder),
+            (tile!("A8"), TileCategory::Grower),
+            (tile!("B1"), TileCategory::Merger),
+            (tile!("A4"), TileCategory::Dead),
+            (tile!("G10"), TileCategory::Neutral),
+        ]);
+
+        // with the last chain founded there's nothing left for e6 to become
+        game.grid.place(tile!("I1"));
+        game.grid.place(tile!("I2"));
+        game.grid.fill_chain(tile!("I1"), Chain::Worldwide);
+        assert_eq!(game.classify_hand(PlayerId(0))[0], (tile!("E6"), TileCategory::TempBlocked));
+    }
+
+    #[test]
+    fn test_no_chains_available() {
+        let mut game = game_test_instance();
+        let starts = ["A1", "C1", "E1", "G1", "I1", "A4", "C4"];
+        for (start, chain) in starts.into_iter().zip(CHAIN_ARRAY) {
+            let start: Tile = start.try_into().unwrap();
+            game.grid.place(start);
+            game.grid.place(Tile::new(start.0.x + 1, start.0.y));
+            game.grid.fill_chain(start.0, chain);
+            assert_eq!(game.no_chains_available(), chain == Chain::Imperial);
+        }
+
+        game.grid.place(tile!("E4"));
+        game.players[0].tiles[0] = tile!("E5");
+        game.players[0].tiles[1] = tile!("E3");
+
+        assert!(game.no_chains_available());
+        assert_eq!(game.grid.get(tile!("E5")), Slot::Empty(Legality::TemporarilyIllegal));
+
+        let actions = game.actions();
+        assert!(!actions.contains(&Action::PlaceTile(PlayerId(0), tile!("E5"))));
+        assert!(actions.contains(&Action::PlaceTile(PlayerId(0), tile!("E3"))));
+    }
+
+    #[test]
+    fn test_action_menu() {
+        let game = purchase_after_merge_instance();
+        let menu = game.action_menu();
+        let actions = game.actions();
+
+        assert_eq!(menu.len(), actions.len());
+        for (idx, (menu_idx, text)) in menu.iter().enumerate() {
+            assert_eq!(*menu_idx, idx);
+            assert!(!text.is_empty());
+            assert_eq!(*text, actions[idx].to_string());
+        }
+
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A4")));
+        assert_eq!(game.action_menu().len(), game.actions().len());
+    }
+
+    #[test]
+    fn test_play_turn() {
+        let game = game_test_instance();
+
+        let mut num_actions = 0;
+        let next = game.play_turn(|_, actions| {
+            num_actions += 1;
+            actions[0].clone()
+        });
+
+        assert_eq!(next.current_player_id, PlayerId(1));
+        assert_eq!(next.turn, game.turn + 1);
+        assert_eq!(next.phase, Phase::AwaitingTilePlacement);
+        assert_eq!(num_actions, 1);
+
+        // a chain founding turn needs three decisions, place, found, then buy
+        let mut game = game_test_instance();
+        let tile = game.players[0].tiles[0];
+        let neighbour = Tile::new(tile.0.x, if tile.0.y == 0 { 1 } else { tile.0.y - 1 });
+        game.tiles.retain(|t| *t != neighbour);
+        for player in &mut game.players {
+            player.tiles.retain(|t| *t != neighbour);
+        }
+        game.grid.place(neighbour);
+
+        let mut num_actions = 0;
+        let next = game.play_turn(|game, actions| {
+            num_actions += 1;
+            actions.iter().find(|action| **action == Action::PlaceTile(game.current_player_id, tile)).unwrap_or(&actions[0]).clone()
+        });
+
+        assert_eq!(next.current_player_id, PlayerId(1));
+        assert_eq!(next.grid.existing_chains().len(), 1);
+        assert_eq!(num_actions, 3);
+    }
+
+    #[test]
+    fn test_tile_placement_actions_sorted() {
+        let mut game = game_test_instance();
+        game.players[0].tiles = vec![tile!("C3"), tile!("A12"), tile!("B1"), tile!("A2")];
+
+        assert_eq!(game.actions(), vec![
+            Action::PlaceTile(PlayerId(0), tile!("A2")),
+            Action::PlaceTile(PlayerId(0), tile!("A12")),
+            Action::PlaceTile(PlayerId(0), tile!("B1")),
+            Action::PlaceTile(PlayerId(0), tile!("C3")),
+        ]);
+    }
+
+    #[test]
+    fn test_actions_deterministic() {
+        for seed in 0..20 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let mut game = Acquire::from_seed(seed, &Options::default());
+
+            while !game.is_terminated() {
+                let actions = game.actions();
+                assert_eq!(actions, game.actions());
+
+                // decoding rebuilds the grid's hash maps, which may iterate in a different order
+                let decoded = Acquire::from_bytes(&game.to_bytes()).unwrap();
+                assert_eq!(actions, decoded.actions());
+
+                let action = actions.choose(&mut rng).expect("an action");
+                game = game.apply_action(action.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lightweight_clone() {
+        for seed in 0..10 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let mut full = Acquire::from_seed(seed, &Options::default()).with_logging();
+            full.grid.indicators.insert(tile!("A1"));
+
+            let mut light = full.lightweight_clone();
+            assert!(light.log().is_empty());
+            assert!(light.grid.indicators.is_empty());
+
+            while !full.is_terminated() {
+                let actions = full.actions();
+                assert_eq!(actions, light.actions());
+
+                let action = actions.choose(&mut rng).expect("an action");
+                full = full.apply_action(action.clone());
+                light = light.apply_action(action.clone());
+
+                assert_eq!(full.to_bytes(), light.to_bytes());
+            }
+
+            assert!(light.is_terminated());
+            assert!(light.log().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_starting_hands() {
+        for deal_one_at_a_time in [false, true] {
+            let options = Options { num_tiles: 5, num_players: 3, deal_one_at_a_time, ..Options::default() };
+            let game = Acquire::from_seed(2, &options);
+
+            for player in &game.players {
+                assert_eq!(player.tiles.len(), 5);
+            }
+
+            let dealt = game.players.iter().flat_map(|player| player.tiles.iter()).collect::<HashSet<_>>();
+            assert_eq!(dealt.len(), 15);
+            assert!(game.tiles.iter().all(|tile| !dealt.contains(tile)));
+        }
+
+        // both ways of dealing take the same tiles off the bag, just handed out in a different order
+        let whole_hands = Acquire::from_seed(2, &Options::default());
+        let one_at_a_time = Acquire::from_seed(2, &Options { deal_one_at_a_time: true, ..Options::default() });
+        assert_eq!(whole_hands.players[0].tiles[1], one_at_a_time.players[1].tiles[0]);
+        assert_eq!(whole_hands.tiles, one_at_a_time.tiles);
+    }
+
+    #[test]
+    fn test_coordinate_style() {
+        let game = game_test_instance();
+        assert_eq!(game.parse_tile("H11").unwrap(), tile!("H11"));
+        assert_eq!(game.format_tile(tile!("H11")), "H11");
+
+        let options = Options { coordinate_style: CoordinateStyle::ColumnLetter, ..Options::default() };
+        let game = Acquire::from_seed(2, &options);
+        assert_eq!(game.parse_tile("K8").unwrap(), tile!("H11"));
+        assert_eq!(game.format_tile(tile!("H11")), "K8");
+
+        let decoded = Acquire::from_bytes(&game.to_bytes()).unwrap();
+        assert_eq!(decoded.format_tile(tile!("H11")), "K8");
+
+        // the menu writes tiles the same way they're parsed
+        let actions = game.actions();
+        for (idx, text) in game.action_menu() {
+            let written = text.rsplit(' ').next().unwrap();
+            assert_eq!(actions[idx], Action::PlaceTile(PlayerId(0), game.parse_tile(written).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_stock_supply() {
+        let game = game_test_instance();
+        assert!(CHAIN_ARRAY.iter().all(|chain| game.stocks.amount(*chain) == 25));
+
+        let supply = ChainTable([10, 12, 14, 16, 18, 20, 22]);
+        let options = Options { num_stock: StockSupply::PerChain(supply.clone()), ..Options::default() };
+        let game = Acquire::from_seed(2, &options);
+
+        for chain in CHAIN_ARRAY {
+            assert_eq!(game.stocks.amount(chain), supply.get(&chain));
+        }
+        assert_eq!(game.stocks.amount(Chain::Tower), 10);
+        assert_eq!(game.stocks.amount(Chain::Imperial), 22);
+
+        let decoded = Acquire::from_bytes(&game.to_bytes()).unwrap();
+        assert_eq!(decoded.options.num_stock, StockSupply::PerChain(supply));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid options")]
+    fn test_invalid_options() {
+        Acquire::from_seed(2, &Options { num_tiles: 0, ..Options::default() });
     }
 
     #[test]
     fn test_game_up_to_merge() {
         let game = game_test_instance();
 
-        let game = game.apply_action(game.actions().remove(0));
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("I11")));
         assert_eq!(game.grid.get(tile!("I11")), Slot::NoChain);
 
-        let game = game.apply_action(game.actions().remove(0));
+        let game = game.apply_action(Action::PlaceTile(PlayerId(1), tile!("H11")));
         assert_eq!(game.grid.get(tile!("H11")), Slot::NoChain);
 
         println!("{}", game);
@@ -916,8 +2297,8 @@ mod test {
 
     #[test]
     fn test_purchase_combinations() {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        let mut game = Acquire::new(rng, &Options::default());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
 
         game.grid.place(tile!("A1"));
         game.grid.place(tile!("A2"));
@@ -970,10 +2351,32 @@ mod test {
         assert_eq!(game.purchasable_combinations(PlayerId(0)).len(), 35);
     }
 
+    #[test]
+    fn test_purchase_combinations_max_buys() {
+        // with two chains on the board there are 3 options (including None) to choose from
+        for (max_buys, expected) in [(1, 3), (2, 6), (3, 10)] {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+            let options = Options { max_buys_per_turn: max_buys, ..Options::default() };
+            let mut game = Acquire::new(&mut rng, &options);
+
+            game.grid.place(tile!("A1"));
+            game.grid.place(tile!("A2"));
+            game.grid.fill_chain(tile!("A1"), Chain::American);
+
+            game.grid.place(tile!("D1"));
+            game.grid.place(tile!("D2"));
+            game.grid.fill_chain(tile!("D1"), Chain::Luxor);
+
+            let combinations = game.purchasable_combinations(PlayerId(0));
+            assert_eq!(combinations.len(), expected);
+            assert!(combinations.iter().all(|combination| combination.len() == max_buys as usize));
+        }
+    }
+
     #[test]
     fn test_player_ids_in_order() {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        let game = Acquire::new(rng, &Options::default());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let game = Acquire::new(&mut rng, &Options::default());
 
         assert_eq!(game.player_ids_in_order(PlayerId(0)), vec![
             PlayerId(0),
@@ -999,8 +2402,8 @@ mod test {
 
     #[test]
     fn test_four_way_merge() {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        let mut game = Acquire::new(rng, &Options::default());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
 
         game.grid.place(tile!("D1"));
         game.grid.place(tile!("D2"));
@@ -1020,7 +2423,7 @@ mod test {
 
         game.players[0].tiles[0] = tile!("D3");
 
-        game = game.apply_action(game.actions().remove(0));
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D3")));
 
         let a = game.actions();
         // should be one action for each way we can merge the chains together
@@ -1040,8 +2443,8 @@ mod test {
 
     #[test]
     fn test_four_way_merge_with_stakes() {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        let mut game = Acquire::new(rng, &Options::default());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
 
         game.grid.place(tile!("D1"));
         game.grid.place(tile!("D2"));
@@ -1059,65 +2462,69 @@ mod test {
         game.grid.place(tile!("F3"));
         game.grid.fill_chain(tile!("F3"), Chain::Tower);
 
-        game.players[0].stocks.deposit(Chain::American, 3);
         game.players[0].stocks.deposit(Chain::Festival, 3);
+        game.players[0].stocks.deposit(Chain::American, 3);
         game.players[0].stocks.deposit(Chain::Continental, 3);
         game.players[0].stocks.deposit(Chain::Tower, 3);
 
-        game.players[1].stocks.deposit(Chain::American, 1);
-        game.players[1].stocks.deposit(Chain::Festival, 2);
+        game.players[1].stocks.deposit(Chain::Festival, 1);
+        game.players[1].stocks.deposit(Chain::American, 2);
         game.players[1].stocks.deposit(Chain::Continental, 3);
         game.players[1].stocks.deposit(Chain::Tower, 4);
 
-        game.players[2].stocks.deposit(Chain::American, 5);
-        game.players[2].stocks.deposit(Chain::Festival, 3);
+        game.players[2].stocks.deposit(Chain::Festival, 5);
+        game.players[2].stocks.deposit(Chain::American, 3);
         game.players[2].stocks.deposit(Chain::Continental, 2);
         game.players[2].stocks.deposit(Chain::Tower, 0);
 
-        game.players[3].stocks.deposit(Chain::American, 8);
-        game.players[3].stocks.deposit(Chain::Festival, 0);
+        game.players[3].stocks.deposit(Chain::Festival, 8);
+        game.players[3].stocks.deposit(Chain::American, 0);
         game.players[3].stocks.deposit(Chain::Continental, 2);
         game.players[3].stocks.deposit(Chain::Tower, 1);
 
 
         game.players[0].tiles[0] = tile!("D3");
 
-        game = game.apply_action(game.actions().remove(0));
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D3")));
 
         // should be one action for each way we can merge the chains together
         assert_eq!(game.actions().len(), 4);
+
+        // tower survives, american is the first defunct chain in enum order
         game = game.apply_action(game.actions().remove(0));
 
 
-        assert_eq!(game.players[0].stocks.amount(Chain::Festival), 3);
+        assert_eq!(game.players[0].stocks.amount(Chain::American), 3);
         assert_eq!(game.players[0].stocks.amount(Chain::Tower), 3);
         assert_eq!(game.players[0].money, 7500);
 
-        // Player 0 sells 1 and trades-in 2 for 1. (Festival)
+        // Player 0 sells 1 and trades-in 2 for 1. (American)
         game = game.apply_action(game.actions().remove(3));
 
-        assert_eq!(game.players[0].stocks.amount(Chain::Festival), 0);
+        assert_eq!(game.players[0].stocks.amount(Chain::American), 0);
         assert_eq!(game.players[0].stocks.amount(Chain::Tower), 4);
         assert_eq!(game.players[0].money, 7800);
 
 
-        assert_eq!(game.players[1].stocks.amount(Chain::Festival), 2);
+        assert_eq!(game.players[1].stocks.amount(Chain::American), 2);
         assert_eq!(game.players[1].money, 6000);
 
-        // Player 1 sells 2. (Festival)
+        // Player 1 sells 2. (American)
         game = game.apply_action(game.actions().remove(3));
 
 
-        assert_eq!(game.players[2].stocks.amount(Chain::Festival), 3);
+        assert_eq!(game.players[2].stocks.amount(Chain::American), 3);
         assert_eq!(game.players[2].money, 7500);
 
         // Player 2 sells 3.
         game = game.apply_action(game.actions().remove(5));
 
-        assert_eq!(game.players[2].stocks.amount(Chain::Festival), 0);
-        assert_eq!(game.players[2].money, 8400);
+        // american is done with, so the festival bonuses are paid out straight away and
+        // player 2 picks up the $1500 minority bonus
+        assert_eq!(game.players[2].stocks.amount(Chain::American), 0);
+        assert_eq!(game.players[2].money, 8400 + 1500);
 
-        // Player 3 has no stake in fesitval
+        // Player 3 has no stake in american
 
 
         match game.phase {
@@ -1130,9 +2537,484 @@ mod test {
         game.apply_action(game.actions().remove(2));
     }
 
+    #[test]
+    fn test_trade_in_limited_by_bank_mid_merge() {
+        let mut game = game_test_instance();
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.place(tile!("A3"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("A5"));
+        game.grid.place(tile!("A6"));
+        game.grid.fill_chain(tile!("A5"), Chain::Festival);
+
+        // the bank only has a single american share left to trade for
+        game.stocks.withdraw(Chain::American, 24).unwrap();
+
+        game.players[0].stocks.deposit(Chain::Festival, 4);
+        game.players[1].stocks.deposit(Chain::Festival, 4);
+
+        game.players[0].tiles[0] = tile!("A4");
+        game = game.apply_action(game.actions().remove(0));
+
+        let trade_in_actions = |game: &Acquire| -> Vec<Action> {
+            game.actions().into_iter().filter(|action| {
+                matches!(action, Action::DecideMerge { decision, .. } if decision.trade_in > 0)
+            }).collect()
+        };
+
+        // player 0 may trade in two festival for the last american
+        assert_eq!(game.actions().len(), 8);
+        let trade_in = trade_in_actions(&game).into_iter().find(|action| {
+            matches!(action, Action::DecideMerge { decision, .. } if decision.sell == 0 && decision.trade_in == 2)
+        }).unwrap();
+        game = game.apply_action(trade_in);
+
+        assert_eq!(game.stocks.amount(Chain::American), 0);
+        assert_eq!(game.players[0].stocks.amount(Chain::American), 1);
+
+        // the bank is now out of american, so player 1 may only sell or keep
+        match game.phase {
+            Phase::Merge { merging_player_id, .. } => assert_eq!(merging_player_id, PlayerId(1)),
+            _ => panic!("game not in correct state"),
+        }
+        assert_eq!(game.actions().len(), 5);
+        assert!(trade_in_actions(&game).is_empty());
+    }
+
+    #[test]
+    fn test_max_turns() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let options = Options {
+            max_turns: Some(10),
+            ..Default::default()
+        };
+
+        let mut game = Acquire::new(&mut rng, &options);
+        while !game.is_terminated() {
+            let actions = game.actions();
+            game = game.apply_action(actions.choose(&mut rng).expect("an action").clone());
+        }
+
+        assert_eq!(game.turn, 10);
+    }
+
+    #[test]
+    fn test_max_turns_pays_final_bonuses() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let options = Options {
+            max_turns: Some(10),
+            ..Default::default()
+        };
+
+        let mut game = Acquire::new(&mut rng, &options);
+        game.turn = 9;
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+        game.players[1].stocks.deposit(Chain::American, 2);
+
+        let projected = game.projected_final_bonuses();
+        assert_eq!(projected[&PlayerId(1)], 3000);
+
+        game.players[0].tiles[0] = tile!("I12");
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("I12")));
+        assert!(!game.is_terminated());
+
+        game = game.apply_action(Action::PurchaseStock(PlayerId(0), vec![BuyOption::None; 3]));
+        assert!(game.is_terminated());
+        assert_eq!(game.turn, 10);
+        assert_eq!(game.players[1].money, 6000 + 3000);
+    }
+
+    fn tied_merge_instance() -> Acquire {
+        let mut game = game_test_instance();
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("A5"));
+        game.grid.fill_chain(tile!("A4"), Chain::Tower);
+
+        game.players[0].tiles[0] = tile!("A3");
+        game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A3")))
+    }
+
+    #[test]
+    fn test_valid_tiebreak_selection() {
+        let game = tied_merge_instance();
+
+        assert_eq!(game.actions(), vec![
+            Action::SelectChainForTiebreak(PlayerId(0), Chain::Tower),
+            Action::SelectChainForTiebreak(PlayerId(0), Chain::American),
+        ]);
+
+        game.apply_action(Action::SelectChainForTiebreak(PlayerId(0), Chain::Tower));
+    }
+
+    #[test]
+    #[should_panic(expected = "Imperial is not one of the chains tied for the merge")]
+    fn test_invalid_tiebreak_selection() {
+        let game = tied_merge_instance();
+        game.apply_action(Action::SelectChainForTiebreak(PlayerId(0), Chain::Imperial));
+    }
+
+    #[test]
+    fn test_tiebreak_defunct_order() {
+        let mut game = game_test_instance();
+
+        game.grid.place(tile!("D1"));
+        game.grid.place(tile!("D2"));
+        game.grid.fill_chain(tile!("D2"), Chain::American);
+
+        game.grid.place(tile!("B3"));
+        game.grid.place(tile!("C3"));
+        game.grid.fill_chain(tile!("C3"), Chain::Continental);
+
+        game.grid.place(tile!("E3"));
+        game.grid.place(tile!("F3"));
+        game.grid.fill_chain(tile!("F3"), Chain::Tower);
+
+        // chains nobody holds are absorbed without waiting on anyone
+        for chain in [Chain::American, Chain::Continental, Chain::Tower] {
+            game.players[1].stocks.deposit(chain, 1);
+        }
+
+        game.players[0].tiles[0] = tile!("D3");
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D3")));
+
+        let defunct_order = |survivor: Chain| -> Vec<Chain> {
+            let game = game.apply_action(Action::SelectChainForTiebreak(PlayerId(0), survivor));
+            let Phase::Merge { mergers_remaining, .. } = game.phase else {
+                panic!("expected to still be merging");
+            };
+
+            mergers_remaining.iter().map(|merger| merger.defunct_chain).collect()
+        };
+
+        // all three are the same size, so the defunct chains go in enum order
+        assert_eq!(defunct_order(Chain::Continental), vec![Chain::Tower, Chain::American]);
+        assert_eq!(defunct_order(Chain::American), vec![Chain::Tower, Chain::Continental]);
+        assert_eq!(defunct_order(Chain::Tower), vec![Chain::American, Chain::Continental]);
+    }
+
+    #[test]
+    fn test_tiebreak_with_smaller_chain() {
+        let mut game = game_test_instance();
+
+        game.grid.place(tile!("D1"));
+        game.grid.place(tile!("D2"));
+        game.grid.place(tile!("D3"));
+        game.grid.fill_chain(tile!("D3"), Chain::American);
+
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("B4"));
+        game.grid.place(tile!("C4"));
+        game.grid.fill_chain(tile!("C4"), Chain::Continental);
+
+        game.grid.place(tile!("E4"));
+        game.grid.place(tile!("F4"));
+        game.grid.fill_chain(tile!("F4"), Chain::Tower);
+
+        for chain in [Chain::American, Chain::Continental, Chain::Tower] {
+            game.players[1].stocks.deposit(chain, 1);
+        }
+
+        game.players[0].tiles[0] = tile!("D4");
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D4")));
+
+        // only the two large chains are up for the tiebreak
+        assert_eq!(game.actions(), vec![
+            Action::SelectChainForTiebreak(PlayerId(0), Chain::American),
+            Action::SelectChainForTiebreak(PlayerId(0), Chain::Continental),
+        ]);
+
+        let mut game = game.apply_action(Action::SelectChainForTiebreak(PlayerId(0), Chain::Continental));
+        let Phase::Merge { mergers_remaining, .. } = &game.phase else {
+            panic!("expected to still be merging");
+        };
+        let defunct_chains: Vec<Chain> = mergers_remaining.iter().map(|merger| merger.defunct_chain).collect();
+        assert_eq!(defunct_chains, vec![Chain::American, Chain::Tower]);
+
+        while !game.is_terminated() && matches!(game.phase, Phase::Merge { .. }) {
+            game = game.apply_action(game.actions().remove(0));
+        }
+
+        // the smaller tower chain was absorbed along with american
+        assert_eq!(game.grid.chain_size(Chain::Continental), 9);
+        assert_eq!(game.grid.chain_size(Chain::American), 0);
+        assert_eq!(game.grid.chain_size(Chain::Tower), 0);
+    }
+
+    #[test]
+    fn test_pending_merge_chains() {
+        let mut game = game_test_instance();
+        assert_eq!(game.pending_merge_chains(), vec![]);
+
+        game.grid.place(tile!("D1"));
+        game.grid.place(tile!("D2"));
+        game.grid.place(tile!("D3"));
+        game.grid.fill_chain(tile!("D3"), Chain::American);
+
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("B4"));
+        game.grid.place(tile!("C4"));
+        game.grid.fill_chain(tile!("C4"), Chain::Continental);
+
+        game.grid.place(tile!("E4"));
+        game.grid.place(tile!("F4"));
+        game.grid.fill_chain(tile!("F4"), Chain::Tower);
+
+        // nobody holds tower, so it never gets a merger of its own
+        game.players[1].stocks.deposit(Chain::American, 1);
+        game.players[1].stocks.deposit(Chain::Continental, 1);
+
+        game.players[0].tiles[0] = tile!("D4");
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D4")));
+        assert_eq!(game.pending_merge_chains(), vec![Chain::Tower]);
+
+        let game = game.apply_action(Action::SelectChainForTiebreak(PlayerId(0), Chain::Continental));
+        assert_eq!(game.current_merger().unwrap().defunct_chain, Chain::American);
+        assert_eq!(game.pending_merge_chains(), vec![Chain::American, Chain::Tower]);
+    }
+
+    #[test]
+    fn test_indicators_cleared_between_turns() {
+        let mut game = game_test_instance();
+
+        game.grid_mut().indicators.insert(tile!("A1"));
+        assert!(game.to_string().contains("[□]"));
+
+        // the first tile on an empty board never forms a chain, so the turn passes immediately
+        let game = game.apply_action(game.actions().remove(0));
+        assert_eq!(game.current_player_id, PlayerId(1));
+        assert!(game.grid().indicators.is_empty());
+    }
+
+    /// player 1 holds A3, which sits between two safe chains
+    fn dead_tile_instance(options: Options) -> Acquire {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options { safe_chain_size: 2, ..options });
+
+        let dead: Tile = tile!("A3");
+        game.tiles.retain(|tile| *tile != dead);
+        for player in &mut game.players {
+            player.tiles.retain(|tile| *tile != dead);
+        }
+        game.players[1].tiles.truncate(5);
+        game.players[1].tiles.push(dead);
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("A5"));
+        game.grid.fill_chain(tile!("A4"), Chain::Tower);
+
+        game
+    }
+
+    #[test]
+    fn test_is_stalemate() {
+        let mut game = game_test_instance();
+        assert!(!game.is_stalemate());
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        // nothing left anywhere but tiles that keep to themselves
+        game.tiles.clear();
+        let hands: [[Tile; 2]; 4] = [
+            [tile!("C5"), tile!("E8")],
+            [tile!("G3"), tile!("I10")],
+            [tile!("C10"), tile!("E1")],
+            [tile!("G12"), tile!("I6")],
+        ];
+        for (player, hand) in game.players.iter_mut().zip(hands) {
+            player.tiles = hand.to_vec();
+        }
+        assert!(game.is_stalemate());
+
+        // a tile that grows tower
+        let mut grows = game.clone();
+        grows.players[2].tiles.push(tile!("A3"));
+        assert!(!grows.is_stalemate());
+
+        // a tile which founds a chain once c5 is down
+        let mut founds = game.clone();
+        founds.tiles.push(tile!("C6"));
+        assert!(!founds.is_stalemate());
+    }
+
+    #[test]
+    fn test_termination_eligibility() {
+        let options = Options { safe_chain_size: 2, game_ending_chain_size: 4, ..Options::default() };
+
+        let mut game = Acquire::from_seed(2, &options);
+        assert_eq!(game.termination_eligibility(), None);
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        // one safe chain isn't enough while the others are still to be founded
+        assert_eq!(game.termination_eligibility(), None);
+
+        game.grid.place(tile!("A3"));
+        game.grid.place(tile!("A4"));
+        assert_eq!(game.termination_eligibility(), Some(TerminationCause::ChainReachedEndSize(Chain::Tower)));
+
+        // every chain founded two tiles big, on every other row
+        let mut game = Acquire::from_seed(2, &options);
+        for (idx, chain) in CHAIN_ARRAY.iter().enumerate() {
+            let y = idx as i8 * 2 % 9;
+            let x = if idx < 5 { 0 } else { 4 };
+            game.grid.place(Tile::new(x, y));
+            game.grid.place(Tile::new(x + 1, y));
+            game.grid.fill_chain(Point { x, y }, *chain);
+        }
+
+        assert_eq!(game.termination_eligibility(), Some(TerminationCause::AllChainsSafe));
+    }
+
+    #[test]
+    fn test_termination_progress() {
+        let mut game = game_test_instance();
+        assert_eq!(game.termination_progress(), TerminationProgress {
+            largest_chain_size: 0,
+            game_ending_size: 41,
+            safe_chain_count: 0,
+            total_chain_count: 7,
+        });
+
+        let rows = [(0, 11, Chain::Tower), (2, 12, Chain::American), (4, 5, Chain::Luxor)];
+        for (y, size, chain) in rows {
+            for x in 0..size {
+                game.grid.place(Tile::new(x, y));
+            }
+            game.grid.fill_chain(Point { x: 0, y }, chain);
+        }
+
+        assert_eq!(game.termination_progress(), TerminationProgress {
+            largest_chain_size: 12,
+            game_ending_size: 41,
+            safe_chain_count: 2,
+            total_chain_count: 7,
+        });
+    }
+
+    #[test]
+    fn test_game_ending_tiles() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        // rows A to C and the start of row D, 40 tiles
+        for tile in Tile::all_for_board(12, 4).take(40).skip(2) {
+            game.grid.place(tile);
+        }
+        assert_eq!(game.grid.chain_size(Chain::Tower), 40);
+
+        game.players[0].tiles = vec![tile!("I12"), tile!("D5"), tile!("E1")];
+        assert_eq!(game.game_ending_tiles(PlayerId(0)), vec![tile!("D5"), tile!("E1")]);
+
+        // a lone tile next to the chain doesn't count until it's connected
+        game.players[0].tiles = vec![tile!("F1")];
+        assert!(game.game_ending_tiles(PlayerId(0)).is_empty());
+    }
+
+    #[test]
+    fn test_has_actions() {
+        let mut game = dead_tile_instance(Options::default());
+        assert!(game.has_actions());
+
+        // player 0's whole hand is the dead tile
+        game.players[0].tiles = vec![tile!("A3")];
+
+        assert!(!game.has_actions());
+        assert!(game.actions().is_empty());
+        assert!(!game.is_terminated());
+    }
+
+    #[test]
+    fn test_discard_dead_tiles() {
+        let mut game = dead_tile_instance(Options::default());
+        let dead: Tile = tile!("A3");
+        let num_tiles_in_bag = game.tiles.len();
+
+        assert_eq!(game.grid.get(dead.0), Slot::Empty(Legality::PermanentIllegal));
+
+        assert_eq!(game.discard_dead_tiles(PlayerId(1)), vec![dead]);
+        assert_eq!(game.players[1].tiles.len(), 6);
+        assert!(!game.players[1].tiles.contains(&dead));
+        assert_eq!(game.tiles.len(), num_tiles_in_bag - 1);
+
+        // nothing left to discard
+        assert!(game.discard_dead_tiles(PlayerId(1)).is_empty());
+    }
+
+    #[test]
+    fn test_auto_discard_dead() {
+        let dead: Tile = tile!("A3");
+
+        let mut game = dead_tile_instance(Options::default());
+        game.move_to_next_player_who_can_play_a_tile();
+        assert_eq!(game.current_player_id, PlayerId(1));
+        assert!(game.players[1].tiles.contains(&dead));
+
+        let mut game = dead_tile_instance(Options { auto_discard_dead: true, ..Options::default() });
+        game.move_to_next_player_who_can_play_a_tile();
+        assert_eq!(game.current_player_id, PlayerId(1));
+        assert!(!game.players[1].tiles.contains(&dead));
+        assert_eq!(game.players[1].tiles.len(), 6);
+    }
+
+    #[test]
+    fn test_player_must_pass() {
+        let mut game = dead_tile_instance(Options::default());
+
+        let placed: [Tile; 5] = [tile!("C1"), tile!("C2"), tile!("C3"), tile!("C4"), tile!("C5")];
+        game.tiles.retain(|tile| !placed.contains(tile));
+        for player in &mut game.players {
+            player.tiles.retain(|tile| !placed.contains(tile));
+        }
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Luxor);
+        game.grid.place(tile!("C4"));
+        game.grid.place(tile!("C5"));
+        game.grid.fill_chain(tile!("C4"), Chain::Festival);
+
+        // both tiles would merge two safe chains
+        let dead: [Tile; 2] = [tile!("A3"), tile!("C3")];
+        game.players[1].tiles = dead.to_vec();
+
+        assert!(!game.player_must_pass(PlayerId(0)));
+        assert!(game.player_must_pass(PlayerId(1)));
+        assert!(!game.player_must_pass(PlayerId(2)));
+
+        let turn = game.turn;
+        game.move_to_next_player_who_can_play_a_tile();
+
+        // player 1 was skipped once, and traded in their tiles on the way past
+        assert_eq!(game.current_player_id, PlayerId(2));
+        assert_eq!(game.turn, turn + 2);
+        assert!(!game.terminated);
+        assert!(dead.iter().all(|tile| !game.players[1].tiles.contains(tile)));
+    }
+
     #[test]
     fn test_growth() {
-        let mut game = Acquire::new(rand_chacha::ChaCha8Rng::seed_from_u64(2), &Options::default());
+        let mut game = Acquire::new(&mut rand_chacha::ChaCha8Rng::seed_from_u64(2), &Options::default());
 
         game.grid.place(tile!("A4"));
         game.grid.place(tile!("B3"));
@@ -1152,7 +3034,7 @@ mod test {
     fn test_random_games() {
         for n in 0..100 {
             let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(n);
-            let mut game = Acquire::new(rand_chacha::ChaCha8Rng::seed_from_u64(n), &Options::default());
+            let mut game = Acquire::new(&mut rand_chacha::ChaCha8Rng::seed_from_u64(n), &Options::default());
 
             for _ in 0..200 {
                 if game.is_terminated() {
@@ -1166,7 +3048,7 @@ mod test {
             }
 
             let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-            let mut game = Acquire::new(rand_chacha::ChaCha8Rng::seed_from_u64(2), &Options::default());
+            let mut game = Acquire::new(&mut rand_chacha::ChaCha8Rng::seed_from_u64(2), &Options::default());
 
             loop {
                 if game.is_terminated() {
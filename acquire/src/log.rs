@@ -0,0 +1,158 @@
+use crate::{Acquire, Action, Phase, PlayerId};
+
+/// A human readable record of a single applied action.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogEntry {
+    pub step: u16,
+    pub turn: u16,
+    pub player_id: PlayerId,
+    pub action: Action,
+    pub phase_after: Phase,
+}
+
+/// Everything that happened in a game which every player saw happen, for checking a game after
+/// the fact. The tiles drawn and the order of the bag aren't included, but replaying the actions
+/// from the same seed and options with `Acquire::validate_transcript` reproduces them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PublicTranscript {
+    pub entries: Vec<LogEntry>,
+}
+
+impl PublicTranscript {
+    pub fn actions(&self) -> Vec<Action> {
+        self.entries.iter().map(|entry| entry.action.clone()).collect()
+    }
+}
+
+/// An applied action along with where it left the game, one line of `action_to_jsonl`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ActionLine {
+    pub turn: u16,
+    pub action: Action,
+    pub phase_after: Phase,
+    /// whoever has the next decision, the merging player during a merge
+    pub next_player_id: PlayerId,
+    pub terminated: bool,
+}
+
+impl Acquire {
+    /// The action as a single line of JSON for streaming a game as it's played. This is called on
+    /// the state the action led to, which is where the phase and next player come from.
+    #[cfg(feature = "serde")]
+    pub fn action_to_jsonl(&self, action: &Action) -> String {
+        let line = ActionLine {
+            turn: self.turn,
+            action: action.clone(),
+            phase_after: self.phase.clone(),
+            next_player_id: self.active_player(),
+            terminated: self.terminated,
+        };
+
+        serde_json::to_string(&line).expect("actions always serialize")
+    }
+
+    /// Enables recording every applied action, the log is carried into each following state.
+    /// The whole log is cloned along with the game on every action, so this is meant for
+    /// debugging and replays rather than search.
+    pub fn with_logging(mut self) -> Self {
+        self.log = Some(vec![]);
+        self
+    }
+
+    /// Every action applied since logging was enabled, which needs to be from the start of the
+    /// game for the transcript to be replayed.
+    pub fn public_transcript(&self) -> PublicTranscript {
+        PublicTranscript {
+            entries: self.log().to_vec(),
+        }
+    }
+
+    /// The recorded log, empty when logging hasn't been enabled.
+    pub fn log(&self) -> &[LogEntry] {
+        self.log.as_deref().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
+    use crate::{Acquire, Options, Phase, PlayerId};
+
+    #[test]
+    fn test_log() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default()).with_logging();
+
+        let mut phases = vec![];
+        for _ in 0..12 {
+            let action = game.actions().remove(0);
+            game = game.apply_action(action);
+            phases.push(game.phase().clone());
+        }
+
+        let log = game.log();
+        assert_eq!(log.len(), 12);
+        assert_eq!(log.iter().map(|entry| entry.phase_after.clone()).collect::<Vec<Phase>>(), phases);
+        assert_eq!(log.iter().map(|entry| entry.step).collect::<Vec<u16>>(), (0..12).collect::<Vec<u16>>());
+
+        // the first tile on the board can't form a chain, so the turn passes straight to player 1
+        assert_eq!(log[0].player_id, PlayerId(0));
+        assert_eq!(log[0].turn, 1);
+        assert_eq!(log[0].phase_after, Phase::AwaitingTilePlacement);
+        assert_eq!(log[1].player_id, PlayerId(1));
+        assert_eq!(log[1].turn, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_action_to_jsonl() {
+        use crate::log::ActionLine;
+
+        let mut game = Acquire::from_seed(2, &Options::default());
+        for _ in 0..30 {
+            let action = game.actions().remove(0);
+            game = game.apply_action(action.clone());
+
+            let line = game.action_to_jsonl(&action);
+            assert!(!line.contains('\n'));
+
+            let parsed: ActionLine = serde_json::from_str(&line).unwrap();
+            assert_eq!(parsed.action, action);
+            assert_eq!(&parsed.phase_after, game.phase());
+            assert_eq!(parsed.turn, game.turn);
+            assert!(!parsed.terminated);
+        }
+    }
+
+    #[test]
+    fn test_public_transcript() {
+        let options = Options::default();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::from_seed(2, &options).with_logging();
+
+        let mut applied = vec![];
+        while !game.is_terminated() && applied.len() < 200 {
+            let action = game.actions().choose(&mut rng).expect("an action").clone();
+            game = game.apply_action(action.clone());
+            applied.push(action);
+        }
+
+        let transcript = game.public_transcript();
+        assert_eq!(transcript.entries.len(), applied.len());
+        assert_eq!(transcript.actions(), applied);
+        assert_eq!(Acquire::validate_transcript(2, &options, &transcript.actions()), Ok(()));
+    }
+
+    #[test]
+    fn test_log_disabled() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let game = Acquire::new(&mut rng, &Options::default());
+        let game = game.apply_action(game.actions().remove(0));
+
+        assert!(game.log().is_empty());
+    }
+}
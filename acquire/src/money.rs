@@ -1,8 +1,14 @@
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
 use ahash::HashMap;
+use itertools::Itertools;
 use lazy_static::lazy_static;
-use crate::{Acquire, PlayerId};
-use crate::chain::Chain;
+use thiserror::Error;
+use crate::{Acquire, AcquireError, Action, BonusRounding, BuyOption, MergePhase, Phase, PlayerId};
+use crate::chain::{Chain, ChainTable, CHAIN_ARRAY};
+use crate::grid::{PlaceTileResult, Point};
 use crate::player::Player;
+use crate::tile::Tile;
 
 lazy_static! {
     static ref CHAIN_TIER_MAP: HashMap<Chain, u8> = {
@@ -20,6 +26,11 @@ lazy_static! {
 }
 
 pub fn chain_value(chain: Chain, size: u16) -> u32 {
+    // a chain that's not on the board is worth nothing, whatever its tier
+    if size < 2 {
+        return 0;
+    }
+
     let tier = CHAIN_TIER_MAP[&chain];
     chain_size_value(size) + tier as u32 * 100
 }
@@ -36,7 +47,375 @@ fn chain_size_value(chain_size: u16) -> u32 {
     }
 }
 
+/// The outcome of founding a chain, see `Acquire::founding_value`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FoundingInfo {
+    pub size: u16,
+    /// the founder gets a share for free, as long as the bank has one left
+    pub free_share: bool,
+    pub share_price: u32,
+}
+
+/// A criterion for ordering players at the end of a game, see `Acquire::ranking_by`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TieBreak {
+    /// cash plus the sale value of every share, see `player_liquidation_value`
+    NetWorth,
+    TotalShares,
+    Cash,
+}
+
+pub const DEFAULT_TIE_BREAKS: [TieBreak; 3] = [TieBreak::NetWorth, TieBreak::TotalShares, TieBreak::Cash];
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum PurchaseParseError {
+    #[error("'{0}' is not the initial of a chain")]
+    UnknownInitial(char),
+    #[error("at most {max} shares can be bought in a turn, not {given}")]
+    TooManyShares {
+        given: usize,
+        max: u8,
+    },
+    #[error(transparent)]
+    Illegal(#[from] AcquireError),
+}
+
+/// A distinct set of shares a player could buy in one turn, with what it would cost them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseOption {
+    pub counts: ChainTable<u8>,
+    pub cost: u32,
+}
+
+impl Display for PurchaseOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let buys = CHAIN_ARRAY
+            .iter()
+            .filter(|chain| self.counts.get(chain) > 0)
+            .map(|chain| format!("{} {}", self.counts.get(chain), chain))
+            .join(" + ");
+
+        if buys.is_empty() {
+            write!(f, "Nothing: ${}", self.cost)
+        } else {
+            write!(f, "{buys}: ${}", self.cost)
+        }
+    }
+}
+
 impl Acquire {
+    /// Cash plus the current sale value of every share held, as if the player sold everything
+    /// right now. Excludes any majority / minority bonuses.
+    pub fn player_liquidation_value(&self, player_id: PlayerId) -> u32 {
+        self.get_player_by_id(player_id).money + self.portfolio_value(player_id)
+    }
+
+    /// Every player from first to last, ordered by `DEFAULT_TIE_BREAKS`.
+    pub fn ranking(&self) -> Vec<PlayerId> {
+        self.ranking_by(&DEFAULT_TIE_BREAKS)
+    }
+
+    /// Every player from first to last, compared on each of `tie_breaks` in turn (highest
+    /// first). Players who are equal on all of them are ordered by lowest id, so there are
+    /// never any ties.
+    pub fn ranking_by(&self, tie_breaks: &[TieBreak]) -> Vec<PlayerId> {
+        let key = |player_id: PlayerId| -> Vec<u32> {
+            let player = self.get_player_by_id(player_id);
+
+            tie_breaks.iter().map(|tie_break| match tie_break {
+                TieBreak::NetWorth => self.player_liquidation_value(player_id),
+                TieBreak::TotalShares => player.stocks.total() as u32,
+                TieBreak::Cash => player.money,
+            }).collect()
+        };
+
+        self.players
+            .iter()
+            .map(|player| player.id)
+            .sorted_by_key(|player_id| (Reverse(key(*player_id)), player_id.0))
+            .collect()
+    }
+
+    /// Every player's net worth, from first to last in `ranking` order.
+    pub fn standings(&self) -> Vec<(PlayerId, u32)> {
+        self.ranking()
+            .into_iter()
+            .map(|player_id| (player_id, self.player_liquidation_value(player_id)))
+            .collect()
+    }
+
+    /// How far ahead of second place the leader's net worth is, 0 when they're tied or there's
+    /// only one player.
+    pub fn winning_margin(&self) -> u32 {
+        match self.standings().as_slice() {
+            [(_, first), (_, second), ..] => first - second,
+            _ => 0,
+        }
+    }
+
+    /// The current sale value of every share the player holds, not counting their cash.
+    pub fn portfolio_value(&self, player_id: PlayerId) -> u32 {
+        self.get_player_by_id(player_id).stocks.iter().fold(0, |acc, (chain, amount)| {
+            acc + chain_value(chain, self.grid.chain_size(chain)) * amount as u32
+        })
+    }
+
+    /// Lists `(chain, old_price, new_price)` for every chain whose share price would change if
+    /// `tile` were placed now, without changing the game. Founding a chain and tied mergers wait
+    /// on a player's choice, so only the chains whose fate is already decided are reported.
+    pub fn price_after_placing(&self, tile: Tile) -> Vec<(Chain, u32, u32)> {
+        let mut grid = self.grid.clone();
+
+        match grid.place(tile) {
+            PlaceTileResult::Merge { mergers } => {
+                grid.fill_chain(tile.0, mergers[0].merging_chain);
+            }
+            PlaceTileResult::Proceed |
+            PlaceTileResult::SelectAvailableChain |
+            PlaceTileResult::DecideTieBreak { .. } |
+            PlaceTileResult::Illegal { .. } => {}
+        }
+
+        CHAIN_ARRAY
+            .iter()
+            .map(|chain| (
+                *chain,
+                chain_value(*chain, self.grid.chain_size(*chain)),
+                chain_value(*chain, grid.chain_size(*chain))
+            ))
+            .filter(|(_, old_price, new_price)| old_price != new_price)
+            .collect()
+    }
+
+    /// What founding `chain` from the unclaimed tiles at `pt` would be worth to the founder.
+    pub fn founding_value(&self, pt: Point, chain: Chain) -> FoundingInfo {
+        let size = self.grid.pending_chain_size(pt);
+
+        FoundingInfo {
+            size,
+            free_share: self.stocks.has_any(chain),
+            share_price: chain_value(chain, size),
+        }
+    }
+
+    /// The total bonus each player would be paid if the game ended right now. Only chains which
+    /// are still on the board pay out.
+    pub fn projected_final_bonuses(&self) -> HashMap<PlayerId, u32> {
+        let mut totals: HashMap<PlayerId, u32> = HashMap::default();
+
+        for chain in self.grid.existing_chains() {
+            for (player_id, bonus) in self.chain_bonus(chain) {
+                *totals.entry(player_id).or_default() += bonus;
+            }
+        }
+
+        totals
+    }
+
+    /// Every purchase the player could make this turn, one per distinct set of shares, so
+    /// there's one entry for each `PurchaseStock` action.
+    pub fn purchase_options(&self, player_id: PlayerId) -> Vec<PurchaseOption> {
+        self.purchasable_combinations(player_id)
+            .into_iter()
+            .map(|buys| {
+                let mut counts: ChainTable<u8> = ChainTable::default();
+                for buy in &buys {
+                    if let BuyOption::Chain(chain) = buy {
+                        counts.set(chain, counts.get(chain) + 1);
+                    }
+                }
+
+                PurchaseOption { counts, cost: self.purchase_cost(&buys) }
+            })
+            .collect()
+    }
+
+    /// What the buys would cost at the chains' current sizes. Doesn't check that the bank has
+    /// the stock.
+    pub fn purchase_cost(&self, buys: &[BuyOption]) -> u32 {
+        buys.iter()
+            .map(|buy| match buy {
+                BuyOption::Chain(chain) => chain_value(*chain, self.grid.chain_size(*chain)),
+                BuyOption::None => 0,
+            })
+            .sum()
+    }
+
+    /// Turns chain initials like "cci" (two Continental and an Imperial) into the matching
+    /// `PurchaseStock` action, checking it's one the player can actually make. An empty string
+    /// buys nothing.
+    pub fn parse_purchase(&self, player_id: PlayerId, initials: &str) -> Result<Action, PurchaseParseError> {
+        let mut chains: Vec<Chain> = initials
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| Chain::from_initial(c).ok_or(PurchaseParseError::UnknownInitial(c)))
+            .try_collect()?;
+
+        let max = self.options.max_buys_per_turn;
+        if chains.len() > max as usize {
+            return Err(PurchaseParseError::TooManyShares { given: chains.len(), max });
+        }
+
+        // the same order `actions` lists them in, chains first then the unused buys
+        chains.sort();
+        let mut buys: Vec<BuyOption> = chains.into_iter().map(BuyOption::Chain).collect();
+        buys.resize(max as usize, BuyOption::None);
+
+        let action = Action::PurchaseStock(player_id, buys);
+        self.check_action(&action)?;
+
+        Ok(action)
+    }
+
+    /// Every chain on the board that the player could buy a share of right now, along with its
+    /// per-share price. Chains the bank has run out of stock for are left out.
+    pub fn affordable_chains(&self, player_id: PlayerId) -> Vec<(Chain, u32)> {
+        let player = self.get_player_by_id(player_id);
+
+        self.current_prices()
+            .into_iter()
+            .filter(|(chain, price)| *price <= player.money && self.stocks.has_any(*chain))
+            .collect()
+    }
+
+    /// The per-share price of every chain on the board.
+    pub fn current_prices(&self) -> Vec<(Chain, u32)> {
+        self.grid.existing_chains()
+            .into_iter()
+            .sorted()
+            .map(|chain| (chain, chain_value(chain, self.grid.chain_size(chain))))
+            .collect()
+    }
+
+    /// How many shares the bank has left of every chain on the board. Chains that aren't on the
+    /// board are left out, their shares can't be bought anyway.
+    pub fn bank_summary(&self) -> Vec<(Chain, u8)> {
+        self.grid.existing_chains()
+            .into_iter()
+            .sorted()
+            .map(|chain| (chain, self.stocks.amount(chain)))
+            .collect()
+    }
+
+    /// What each share of the defunct chain sells for in the merger being decided on, None
+    /// outside of a merge decision.
+    pub fn merge_sell_price(&self) -> Option<u32> {
+        let Phase::Merge { phase: MergePhase::AwaitingMergeDecision, .. } = self.phase else {
+            return None;
+        };
+        let defunct_chain = self.current_merger()?.defunct_chain;
+
+        Some(chain_value(defunct_chain, self.grid.chain_size(defunct_chain)))
+    }
+
+    /// Whether buying `additional` shares of `chain` would make the player a majority holder
+    /// (shared or outright) when they aren't one already.
+    pub fn would_gain_majority(&self, player_id: PlayerId, chain: Chain, additional: u8) -> bool {
+        let held = self.get_player_by_id(player_id).stocks.amount(chain);
+        let most_held_by_others = self.most_held_by_others(player_id, chain);
+
+        let is_majority_holder = |amount: u8| amount > 0 && amount >= most_held_by_others;
+
+        !is_majority_holder(held) && is_majority_holder(held + additional)
+    }
+
+    /// The fraction of the chain's shares out of the bank that the player holds, 0 when nobody
+    /// holds any.
+    pub fn player_influence(&self, player_id: PlayerId, chain: Chain) -> f64 {
+        let issued = self.options.num_stock.amount(chain).saturating_sub(self.stocks.amount(chain));
+        if issued == 0 {
+            return 0.0;
+        }
+
+        self.get_player_by_id(player_id).stocks.amount(chain) as f64 / issued as f64
+    }
+
+    /// Whether the player holds more of the chain than anyone else could, even if they bought up
+    /// every share left in the bank.
+    pub fn is_uncontested_leader(&self, player_id: PlayerId, chain: Chain) -> bool {
+        let held = self.get_player_by_id(player_id).stocks.amount(chain);
+        held as u16 > self.most_held_by_others(player_id, chain) as u16 + self.stocks.amount(chain) as u16
+    }
+
+    fn most_held_by_others(&self, player_id: PlayerId, chain: Chain) -> u8 {
+        self.players
+            .iter()
+            .filter(|player| player.id != player_id)
+            .map(|player| player.stocks.amount(chain))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Splits a bonus between `num_players` according to `Options::bonus_rounding`, returning each
+    /// player's share and whatever is left over for the majority holder.
+    fn split_bonus(&self, total: u32, num_players: usize) -> (u32, u32) {
+        let even_split = total / num_players as u32;
+
+        match self.options.bonus_rounding {
+            BonusRounding::RoundUpEach => (round_up_to_nearest_hundred(even_split), 0),
+            BonusRounding::RoundDownEach => (round_down_to_nearest_hundred(even_split), 0),
+            BonusRounding::RemainderToMajority => {
+                let share = round_down_to_nearest_hundred(even_split);
+                (share, total - share * num_players as u32)
+            }
+        }
+    }
+
+    /// The player's share of `chain_bonus`, without building the whole map. 0 for players who
+    /// don't hold the chain.
+    pub fn player_chain_bonus(&self, player_id: PlayerId, chain: Chain) -> u32 {
+        let held = self.get_player_by_id(player_id).stocks.amount(chain);
+        if held == 0 {
+            return 0;
+        }
+
+        let mut most_stock_held = 0;
+        let mut second_most_stock_held = 0;
+        for player in &self.players {
+            let amount = player.stocks.amount(chain);
+            if amount > most_stock_held {
+                second_most_stock_held = most_stock_held;
+                most_stock_held = amount;
+            } else if amount < most_stock_held && amount > second_most_stock_held {
+                second_most_stock_held = amount;
+            }
+        }
+
+        let count_holding = |amount: u8| self.players.iter().filter(|p| p.stocks.amount(chain) == amount).count();
+        let num_most = count_holding(most_stock_held);
+        let num_second = if second_most_stock_held == 0 { 0 } else { count_holding(second_most_stock_held) };
+
+        let chain_value = chain_value(chain, self.grid.chain_size(chain));
+        let total_major_bonus = chain_value * 10;
+        let total_minor_bonus = chain_value * 5;
+
+        if num_most > 1 || num_second == 0 {
+            if held != most_stock_held {
+                return 0;
+            }
+
+            let (split_bonus, remainder) = self.split_bonus(total_major_bonus, num_most);
+
+            // the first tied player in seat order takes the leftover, same as chain_bonus
+            let first_tied = self.players.iter().find(|p| p.stocks.amount(chain) == most_stock_held).unwrap();
+            if first_tied.id == player_id {
+                split_bonus + remainder
+            } else {
+                split_bonus
+            }
+        } else {
+            let (split_minor_bonus, remainder) = self.split_bonus(total_minor_bonus, num_second);
+
+            if held == most_stock_held {
+                total_major_bonus + remainder
+            } else if held == second_most_stock_held {
+                split_minor_bonus
+            } else {
+                0
+            }
+        }
+    }
+
     pub fn chain_bonus(&self, chain: Chain) -> HashMap<PlayerId, u32> {
         let players_with_stock: Vec<&Player> = self.players
             .iter()
@@ -88,14 +467,20 @@ impl Acquire {
 
         // share first place rewards combined, second place gets shit all
         if players_with_most_stock.len() > 1 || (players_with_most_stock.len() == 1 && players_with_second_most_stock.is_empty()) {
-            let split_bonus = round_up_to_nearest_hundred(total_major_bonus / players_with_most_stock.len() as u32);
-            return players_with_most_stock.iter().map(|player| (player.id, split_bonus)).collect();
+            let (split_bonus, remainder) = self.split_bonus(total_major_bonus, players_with_most_stock.len());
+            let mut map: HashMap<PlayerId, u32> = players_with_most_stock.iter().map(|player| (player.id, split_bonus)).collect();
+
+            // players are in seat order, so the leftover goes to the first tied player at the table
+            *map.get_mut(&players_with_most_stock[0].id).unwrap() += remainder;
+
+            return map;
         } else if players_with_most_stock.len() == 1 && !players_with_second_most_stock.is_empty() {
             let mut map = HashMap::default();
 
-            map.insert(players_with_most_stock[0].id, total_major_bonus);
+            let (split_minor_bonus, remainder) = self.split_bonus(total_minor_bonus, players_with_second_most_stock.len());
+
+            map.insert(players_with_most_stock[0].id, total_major_bonus + remainder);
 
-            let split_minor_bonus = round_up_to_nearest_hundred(total_minor_bonus / players_with_second_most_stock.len() as u32);
             for player in players_with_second_most_stock {
                 map.insert(player.id, split_minor_bonus);
             }
@@ -111,22 +496,590 @@ fn round_up_to_nearest_hundred(num: u32) -> u32 {
     ((num + 99) / 100) * 100
 }
 
+fn round_down_to_nearest_hundred(num: u32) -> u32 {
+    (num / 100) * 100
+}
+
 #[cfg(test)]
 mod test {
     use rand::SeedableRng;
-    use crate::{Acquire, Options, tile};
+    use rand::seq::SliceRandom;
+    use crate::{Acquire, AcquireError, Action, BonusRounding, BuyOption, Options, Phase, PlayerId, tile};
     use crate::chain::Chain;
-    use crate::money::round_up_to_nearest_hundred;
+    use crate::money::{chain_value, round_up_to_nearest_hundred, FoundingInfo, PurchaseOption, PurchaseParseError, TieBreak};
+    use crate::tile::Tile;
 
     #[test]
     fn test_bonus_calc() {
-        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
-        let mut game = Acquire::new(rng, &Options::default());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+    }
+
+    #[test]
+    fn test_portfolio_value() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        assert_eq!(game.portfolio_value(PlayerId(0)), 0);
+        assert_eq!(game.players[0].stocks.total(), 0);
+
+        // tower is $200 a share and imperial $400
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Imperial);
+
+        game.players[0].stocks.deposit(Chain::Tower, 3);
+        game.players[0].stocks.deposit(Chain::Imperial, 2);
+
+        assert_eq!(game.players[0].stocks.total(), 5);
+        assert_eq!(game.portfolio_value(PlayerId(0)), 3 * 200 + 2 * 400);
+        assert_eq!(game.player_liquidation_value(PlayerId(0)), 6000 + 1400);
+    }
+
+    #[test]
+    fn test_off_board_value() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        game.players[0].stocks.deposit(Chain::Imperial, 2);
+        game.players[0].stocks.deposit(Chain::Continental, 1);
+        game.players[0].stocks.deposit(Chain::American, 3);
+
+        for chain in [Chain::Imperial, Chain::Continental, Chain::American] {
+            assert_eq!(chain_value(chain, 0), 0);
+        }
+        assert_eq!(game.portfolio_value(PlayerId(0)), 0);
+        assert_eq!(game.player_liquidation_value(PlayerId(0)), 6000);
+
+        // once founded the tier counts again
+        assert_eq!(chain_value(Chain::Imperial, 2), 400);
+        assert_eq!(chain_value(Chain::American, 2), 300);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+
+        // tower isn't on the board, so the shares don't add to anyone's net worth
+        game.players[2].stocks.deposit(Chain::Tower, 2);
+        game.players[3].stocks.deposit(Chain::Tower, 1);
+
+        for player in &mut game.players {
+            player.money = 5000;
+        }
+        game.players[1].money = 7000;
+
+        assert_eq!(game.ranking(), vec![PlayerId(1), PlayerId(2), PlayerId(3), PlayerId(0)]);
+
+        // on cash alone, 0, 2 and 3 are identical and fall back to seat order
+        assert_eq!(game.ranking_by(&[TieBreak::Cash]), vec![PlayerId(1), PlayerId(0), PlayerId(2), PlayerId(3)]);
+
+        // once tower is on the board, player 2's shares are worth more than player 1's extra cash
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+        game.players[2].stocks.deposit(Chain::Tower, 9);
+
+        assert_eq!(game.ranking()[0], PlayerId(2));
+    }
+
+    #[test]
+    fn test_standings() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::from_seed(2, &Options { max_turns: Some(40), ..Options::default() });
+        while !game.is_terminated() {
+            let action = game.actions().choose(&mut rng).expect("an action").clone();
+            game = game.apply_action(action);
+        }
+
+        let standings = game.standings();
+        assert_eq!(standings.len(), 4);
+        assert_eq!(standings.iter().map(|(player_id, _)| *player_id).collect::<Vec<_>>(), game.ranking());
+        assert!(standings.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        for (player_id, net_worth) in &standings {
+            assert_eq!(*net_worth, game.player_liquidation_value(*player_id));
+        }
+
+        assert_eq!(game.winning_margin(), standings[0].1 - standings[1].1);
+
+        let solo = Acquire::from_seed(2, &Options { num_players: 1, ..Options::default() });
+        assert_eq!(solo.winning_margin(), 0);
+    }
+
+    #[test]
+    fn test_liquidation_value() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
 
         game.grid.place(tile!("A1"));
         game.grid.place(tile!("A2"));
+        game.grid.place(tile!("A3"));
+        game.grid.place(tile!("A4"));
+        game.grid.place(tile!("A5"));
         game.grid.fill_chain(tile!("A1"), Chain::American);
 
+        game.players[0].money = 1000;
+        game.players[0].stocks.deposit(Chain::American, 3);
+
+        // american is a mid tier chain, so $600 per share at size 5
+        assert_eq!(game.player_liquidation_value(PlayerId(0)), 1000 + 3 * 600);
+
+        // holding nothing is worth exactly the cash on hand
+        assert_eq!(game.player_liquidation_value(PlayerId(1)), 6000);
+    }
+
+    #[test]
+    fn test_bank_summary() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        assert!(game.bank_summary().is_empty());
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Imperial);
+
+        game.phase = Phase::AwaitingStockPurchase;
+        let game = game.apply_action(Action::PurchaseStock(PlayerId(0), vec![
+            BuyOption::Chain(Chain::Tower),
+            BuyOption::Chain(Chain::Tower),
+            BuyOption::Chain(Chain::Imperial),
+        ]));
+
+        // luxor still has all of its shares but isn't on the board
+        assert_eq!(game.bank_summary(), vec![(Chain::Tower, 23), (Chain::Imperial, 24)]);
+    }
+
+    #[test]
+    fn test_merge_sell_price() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        assert_eq!(game.merge_sell_price(), None);
+
+        for x in 1..=4 {
+            game.grid.place(Tile::new(x - 1, 0));
+        }
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("A6"));
+        game.grid.place(tile!("A7"));
+        game.grid.place(tile!("A8"));
+        game.grid.fill_chain(tile!("A6"), Chain::Luxor);
+        game.players[1].stocks.deposit(Chain::Luxor, 2);
+
+        game.players[0].tiles[0] = tile!("A5");
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("A5")));
+        assert_eq!(game.current_merger().unwrap().defunct_chain, Chain::Luxor);
+
+        // luxor is the cheapest tier, $300 a share at size 3
+        assert_eq!(game.merge_sell_price(), Some(chain_value(Chain::Luxor, 3)));
+        assert_eq!(game.merge_sell_price(), Some(300));
+    }
+
+    #[test]
+    fn test_price_after_placing() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        for x in 1..=10 {
+            game.grid.place(Tile::new(x - 1, 0));
+        }
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+        assert_eq!(game.grid.chain_size(Chain::American), 10);
+
+        // growing american from 10 to 11 crosses into the next price bracket
+        assert_eq!(game.price_after_placing(tile!("A11")), vec![(Chain::American, 700, 800)]);
+
+        // a lone tile away from any chain changes no prices
+        assert_eq!(game.price_after_placing(tile!("E5")), vec![]);
+
+        // merging a small chain into american reports both the survivor and the defunct chain
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Tower);
+        assert_eq!(game.price_after_placing(tile!("B1")), vec![
+            (Chain::Tower, 200, 0),
+            (Chain::American, 700, 800),
+        ]);
+
+        // nothing was actually placed
+        assert_eq!(game.grid.chain_size(Chain::American), 10);
+        assert_eq!(game.grid.chain_size(Chain::Tower), 2);
+    }
+
+    #[test]
+    fn test_projected_final_bonuses() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.place(tile!("A3"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Tower);
+
+        game.players[0].stocks.deposit(Chain::American, 4);
+        game.players[1].stocks.deposit(Chain::American, 2);
+        game.players[1].stocks.deposit(Chain::Tower, 1);
+        game.players[2].stocks.deposit(Chain::Tower, 1);
+
+        // holding stock in a chain that isn't on the board pays nothing
+        game.players[3].stocks.deposit(Chain::Imperial, 5);
+
+        let projected = game.projected_final_bonuses();
+
+        // american is worth $400 a share at size 3, tower $200 at size 2
+        assert_eq!(projected[&PlayerId(0)], 4000);
+        assert_eq!(projected[&PlayerId(1)], 2000 + 1000);
+        assert_eq!(projected[&PlayerId(2)], 1000);
+        assert!(!projected.contains_key(&PlayerId(3)));
+
+        let terminated = game.apply_action(Action::Terminate(PlayerId(0), true));
+        for player in &game.players {
+            let delta = terminated.players[player.id.0 as usize].money - player.money;
+            assert_eq!(delta, projected.get(&player.id).copied().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    fn test_founding_value() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.place(tile!("B1"));
+
+        let info = game.founding_value(tile!("B1"), Chain::Imperial);
+        assert_eq!(info, FoundingInfo { size: 3, free_share: true, share_price: 500 });
+
+        let info = game.founding_value(tile!("A2"), Chain::Tower);
+        assert_eq!(info, FoundingInfo { size: 3, free_share: true, share_price: 300 });
+
+        game.stocks.withdraw(Chain::Tower, 25).unwrap();
+        assert!(!game.founding_value(tile!("A2"), Chain::Tower).free_share);
+
+        // nothing to found on an empty cell
+        assert_eq!(game.founding_value(tile!("E5"), Chain::Tower).size, 0);
+    }
+
+    #[test]
+    fn test_current_prices() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        assert!(game.current_prices().is_empty());
+
+        let rows = [("A", 2, Chain::Tower), ("C", 6, Chain::American), ("E", 11, Chain::Continental)];
+        for (row, size, chain) in rows {
+            for x in 1..=size {
+                let tile: Tile = format!("{}{}", row, x).as_str().try_into().unwrap();
+                game.grid.place(tile);
+            }
+            let first: Tile = format!("{}1", row).as_str().try_into().unwrap();
+            game.grid.fill_chain(first.0, chain);
+        }
+
+        assert_eq!(game.current_prices(), vec![
+            (Chain::Tower, 200),
+            (Chain::American, 700),
+            (Chain::Continental, 900),
+        ]);
+    }
+
+    #[test]
+    fn test_purchase_cost() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+
+        let rows = [("A", 2, Chain::Tower), ("C", 6, Chain::Continental)];
+        for (row, size, chain) in rows {
+            for x in 1..=size {
+                let tile: Tile = format!("{}{}", row, x).as_str().try_into().unwrap();
+                game.grid.place(tile);
+            }
+            let first: Tile = format!("{}1", row).as_str().try_into().unwrap();
+            game.grid.fill_chain(first.0, chain);
+        }
+
+        // $800 for each continental and $200 for the tower
+        let buys = [BuyOption::Chain(Chain::Continental), BuyOption::None, BuyOption::Chain(Chain::Tower), BuyOption::Chain(Chain::Continental)];
+        assert_eq!(game.purchase_cost(&buys), 1800);
+        assert_eq!(game.purchase_cost(&[BuyOption::None; 3]), 0);
+        assert_eq!(game.purchase_cost(&[]), 0);
+    }
+
+    #[test]
+    fn test_purchase_options() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+
+        // tower is $200 a share and american $300
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::American);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Tower);
+
+        let options = game.purchase_options(PlayerId(0));
+        assert_eq!(options.len(), game.purchasable_combinations(PlayerId(0)).len());
+
+        // nothing, 1 of either, 2 of one or 1 of each, then 3 of one or 2 + 1 either way round
+        assert_eq!(options.len(), 1 + 2 + 3 + 4);
+
+        let two_american = options.iter().find(|option| {
+            option.counts.get(&Chain::American) == 2 && option.counts.get(&Chain::Tower) == 1
+        }).unwrap();
+        assert_eq!(two_american.cost, 800);
+        assert_eq!(two_american.to_string(), "1 Tower + 2 American: $800");
+
+        let nothing = options.iter().find(|option| option.cost == 0).unwrap();
+        assert_eq!(*nothing, PurchaseOption { counts: Default::default(), cost: 0 });
+
+        // anything over $500 is out of reach
+        game.players[0].money = 500;
+        assert!(game.purchase_options(PlayerId(0)).iter().all(|option| option.cost <= 500));
+    }
+
+    #[test]
+    fn test_parse_purchase() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::Continental);
+
+        game.grid.place(tile!("E1"));
+        game.grid.place(tile!("E2"));
+        game.grid.fill_chain(tile!("E1"), Chain::Imperial);
+
+        game.phase = Phase::AwaitingStockPurchase;
+
+        let action = game.parse_purchase(PlayerId(0), "cci").unwrap();
+        assert_eq!(action, Action::PurchaseStock(PlayerId(0), vec![
+            BuyOption::Chain(Chain::Continental),
+            BuyOption::Chain(Chain::Continental),
+            BuyOption::Chain(Chain::Imperial),
+        ]));
+        assert!(game.actions().contains(&action));
+
+        // order and case don't matter
+        assert_eq!(game.parse_purchase(PlayerId(0), "I C c").unwrap(), action);
+
+        let action = game.parse_purchase(PlayerId(0), "ttt").unwrap();
+        assert_eq!(action, Action::PurchaseStock(PlayerId(0), vec![BuyOption::Chain(Chain::Tower); 3]));
+
+        let action = game.parse_purchase(PlayerId(0), "").unwrap();
+        assert_eq!(action, Action::PurchaseStock(PlayerId(0), vec![BuyOption::None; 3]));
+        assert!(game.actions().contains(&action));
+
+        assert_eq!(game.parse_purchase(PlayerId(0), "xyz"), Err(PurchaseParseError::UnknownInitial('x')));
+        assert_eq!(game.parse_purchase(PlayerId(0), "tttt"), Err(PurchaseParseError::TooManyShares { given: 4, max: 3 }));
+
+        // luxor isn't on the board and imperial is $400 a share
+        assert!(matches!(game.parse_purchase(PlayerId(0), "l"), Err(PurchaseParseError::Illegal(AcquireError::IllegalAction(_)))));
+        game.players[0].money = 700;
+        assert!(matches!(game.parse_purchase(PlayerId(0), "ii"), Err(PurchaseParseError::Illegal(AcquireError::InsufficientFunds(_)))));
+    }
+
+    #[test]
+    fn test_affordable_chains() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        // tower is $200 a share, american $300 and continental $400
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Continental);
+
+        game.grid.place(tile!("C1"));
+        game.grid.place(tile!("C2"));
+        game.grid.fill_chain(tile!("C1"), Chain::American);
+
+        game.grid.place(tile!("E1"));
+        game.grid.place(tile!("E2"));
+        game.grid.fill_chain(tile!("E1"), Chain::Tower);
+
+        game.players[0].money = 300;
+        assert_eq!(game.affordable_chains(PlayerId(0)), vec![(Chain::Tower, 200), (Chain::American, 300)]);
+
+        game.players[0].money = 199;
+        assert!(game.affordable_chains(PlayerId(0)).is_empty());
+
+        // can't buy what the bank doesn't have
+        game.players[0].money = 6000;
+        game.stocks.withdraw(Chain::American, 25).unwrap();
+        assert_eq!(game.affordable_chains(PlayerId(0)), vec![(Chain::Tower, 200), (Chain::Continental, 400)]);
+    }
+
+    #[test]
+    fn test_would_gain_majority() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        game.players[0].stocks.deposit(Chain::Luxor, 3);
+        game.players[1].stocks.deposit(Chain::Luxor, 4);
+
+        // one share ties the leader, two puts player 0 ahead
+        assert!(!game.would_gain_majority(PlayerId(0), Chain::Luxor, 0));
+        assert!(game.would_gain_majority(PlayerId(0), Chain::Luxor, 1));
+        assert!(game.would_gain_majority(PlayerId(0), Chain::Luxor, 2));
+
+        // the leader can't gain what they already have
+        assert!(!game.would_gain_majority(PlayerId(1), Chain::Luxor, 1));
+
+        // nobody holds any imperial, so any purchase takes the majority
+        assert!(game.would_gain_majority(PlayerId(2), Chain::Imperial, 1));
+        assert!(!game.would_gain_majority(PlayerId(2), Chain::Imperial, 0));
+    }
+
+    #[test]
+    fn test_player_influence() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        assert_eq!(game.player_influence(PlayerId(0), Chain::Tower), 0.0);
+
+        // 10 shares out of the bank, split 6 / 3 / 1
+        for (player_id, amount) in [(0, 6), (1, 3), (2, 1)] {
+            game.stocks.withdraw(Chain::Tower, amount).unwrap();
+            game.players[player_id].stocks.deposit(Chain::Tower, amount);
+        }
+
+        assert_eq!(game.player_influence(PlayerId(0), Chain::Tower), 0.6);
+        assert_eq!(game.player_influence(PlayerId(1), Chain::Tower), 0.3);
+        assert_eq!(game.player_influence(PlayerId(2), Chain::Tower), 0.1);
+        assert_eq!(game.player_influence(PlayerId(3), Chain::Tower), 0.0);
+
+        // 15 shares are still in the bank, plenty for player 1 to catch up
+        assert!(!game.is_uncontested_leader(PlayerId(0), Chain::Tower));
+
+        game.stocks.withdraw(Chain::Tower, 13).unwrap();
+        game.players[0].stocks.deposit(Chain::Tower, 13);
+        assert!(game.is_uncontested_leader(PlayerId(0), Chain::Tower));
+        assert!(!game.is_uncontested_leader(PlayerId(1), Chain::Tower));
+    }
+
+    fn tied_bonus_instance(bonus_rounding: BonusRounding) -> Acquire {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options { bonus_rounding, ..Options::default() });
+
+        // tower is worth $200 a share, so $2000 majority and $1000 minority bonuses
+        game.grid.place(tile!("A1"));
+        game.grid.place(tile!("A2"));
+        game.grid.fill_chain(tile!("A1"), Chain::Tower);
+
+        game
+    }
+
+    #[test]
+    fn test_player_chain_bonus() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+
+        // the board and stakes from lib's test_four_way_merge_with_stakes, every chain is 2 big
+        for (a, b, chain) in [("D1", "D2", Chain::American), ("D4", "D5", Chain::Festival), ("B3", "C3", Chain::Continental), ("E3", "F3", Chain::Tower)] {
+            game.grid.place(a.try_into().unwrap());
+            game.grid.place(b.try_into().unwrap());
+            game.grid.fill_chain(b.try_into().unwrap(), chain);
+        }
+
+        let stakes = [
+            [3, 3, 3, 3],
+            [1, 2, 3, 4],
+            [5, 3, 2, 0],
+            [8, 0, 2, 1],
+        ];
+        for (player, stake) in game.players.iter_mut().zip(stakes) {
+            for (chain, amount) in [Chain::Festival, Chain::American, Chain::Continental, Chain::Tower].into_iter().zip(stake) {
+                player.stocks.deposit(chain, amount);
+            }
+        }
+
+        // festival: player 3 has the majority and player 2 the minority
+        assert_eq!(game.player_chain_bonus(PlayerId(3), Chain::Festival), 3000);
+        assert_eq!(game.player_chain_bonus(PlayerId(2), Chain::Festival), 1500);
+        assert_eq!(game.player_chain_bonus(PlayerId(0), Chain::Festival), 0);
+
+        // american: players 0 and 2 tie for the majority
+        assert_eq!(game.player_chain_bonus(PlayerId(0), Chain::American), 1500);
+        assert_eq!(game.player_chain_bonus(PlayerId(2), Chain::American), 1500);
+        assert_eq!(game.player_chain_bonus(PlayerId(1), Chain::American), 0);
+
+        // tower: player 2 holds none at all
+        assert_eq!(game.player_chain_bonus(PlayerId(1), Chain::Tower), 2000);
+        assert_eq!(game.player_chain_bonus(PlayerId(0), Chain::Tower), 1000);
+        assert_eq!(game.player_chain_bonus(PlayerId(2), Chain::Tower), 0);
+
+        for chain in [Chain::Festival, Chain::American, Chain::Continental, Chain::Tower, Chain::Luxor] {
+            let bonus = game.chain_bonus(chain);
+            for player in &game.players {
+                assert_eq!(game.player_chain_bonus(player.id, chain), bonus.get(&player.id).copied().unwrap_or(0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bonus_rounding_majority_tie() {
+        let expected = [
+            (BonusRounding::RoundUpEach, [700, 700, 700]),
+            (BonusRounding::RoundDownEach, [600, 600, 600]),
+            (BonusRounding::RemainderToMajority, [800, 600, 600]),
+        ];
+
+        for (bonus_rounding, [a, b, c]) in expected {
+            let mut game = tied_bonus_instance(bonus_rounding);
+            for player in &mut game.players[0..3] {
+                player.stocks.deposit(Chain::Tower, 3);
+            }
+
+            let bonus = game.chain_bonus(Chain::Tower);
+            assert_eq!(bonus[&PlayerId(0)], a, "{:?}", bonus_rounding);
+            assert_eq!(bonus[&PlayerId(1)], b, "{:?}", bonus_rounding);
+            assert_eq!(bonus[&PlayerId(2)], c, "{:?}", bonus_rounding);
+            assert!(!bonus.contains_key(&PlayerId(3)));
+            assert_eq!(game.player_chain_bonus(PlayerId(0), Chain::Tower), a, "{:?}", bonus_rounding);
+
+            if bonus_rounding != BonusRounding::RoundUpEach {
+                assert!(bonus.values().sum::<u32>() <= 2000);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bonus_rounding_minority_tie() {
+        let expected = [
+            (BonusRounding::RoundUpEach, 2000, 400),
+            (BonusRounding::RoundDownEach, 2000, 300),
+            (BonusRounding::RemainderToMajority, 2100, 300),
+        ];
+
+        for (bonus_rounding, majority, minority) in expected {
+            let mut game = tied_bonus_instance(bonus_rounding);
+            game.players[0].stocks.deposit(Chain::Tower, 5);
+            for player in &mut game.players[1..4] {
+                player.stocks.deposit(Chain::Tower, 2);
+            }
+
+            let bonus = game.chain_bonus(Chain::Tower);
+            assert_eq!(bonus[&PlayerId(0)], majority, "{:?}", bonus_rounding);
+            for id in 1..4 {
+                assert_eq!(bonus[&PlayerId(id)], minority, "{:?}", bonus_rounding);
+            }
+
+            if bonus_rounding != BonusRounding::RoundUpEach {
+                assert!(bonus.values().sum::<u32>() <= 2000 + 1000);
+            }
+        }
     }
 
     #[test]
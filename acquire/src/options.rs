@@ -0,0 +1,246 @@
+use thiserror::Error;
+use crate::PlayerId;
+use crate::chain::{Chain, ChainTable, NUM_CHAINS};
+use crate::grid::{DEFAULT_GAME_ENDING_CHAIN_SIZE, DEFAULT_SAFE_CHAIN_SIZE};
+use crate::tile::CoordinateStyle;
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum OptionsError {
+    #[error("a game needs at least one player")]
+    NoPlayers,
+    #[error("players need at least one tile in hand to take a turn")]
+    NoTiles,
+    #[error("the board must be at least 1x1, with no more than 26 lettered rows or columns")]
+    InvalidBoardSize,
+    #[error("the board has {available} tiles but {required} are needed to deal every hand")]
+    NotEnoughTiles {
+        available: u16,
+        required: u16,
+    },
+    #[error("the official rules are for 2 to 6 players, not {0}")]
+    UnofficialPlayerCount(u8),
+    #[error("a game can have between 1 and 7 chains, not {0}")]
+    InvalidChainCount(u8),
+    #[error("player {starting_player} can't start a game of {num_players} players")]
+    InvalidStartingPlayer {
+        starting_player: u8,
+        num_players: u8,
+    },
+}
+
+/// How a bonus is divided when it's split between several players.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BonusRounding {
+    /// everyone's share is rounded up to the nearest $100, the bank covers the difference
+    #[default]
+    RoundUpEach,
+    /// everyone's share is rounded down to the nearest $100, the bank keeps the difference
+    RoundDownEach,
+    /// shares are rounded down and what's left goes to the majority holder. when the majority
+    /// itself is tied, it goes to the tied player who comes first in seat order.
+    RemainderToMajority,
+}
+
+/// How many shares of each chain the bank starts with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StockSupply {
+    Uniform(u8),
+    PerChain(ChainTable<u8>),
+}
+
+impl StockSupply {
+    pub fn amount(&self, chain: Chain) -> u8 {
+        match self {
+            StockSupply::Uniform(amount) => *amount,
+            StockSupply::PerChain(amounts) => amounts.get(&chain),
+        }
+    }
+}
+
+impl Default for StockSupply {
+    fn default() -> Self {
+        StockSupply::Uniform(25)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub num_players: u8,
+    pub num_tiles: u8,
+    pub grid_width: u8,
+    pub grid_height: u8,
+    pub num_stock: StockSupply,
+    pub starting_money: u32,
+    /// chains of at least this size can no longer be merged into another chain
+    pub safe_chain_size: u16,
+    /// a chain of at least this size allows the game to be ended
+    pub game_ending_chain_size: u16,
+    /// the game is forcibly ended, with final bonuses paid, once this turn is reached
+    pub max_turns: Option<u16>,
+    /// how many stocks a player may buy at the end of their turn
+    pub max_buys_per_turn: u8,
+    /// dead tiles are also traded in at the start of each turn, not just after buying stock
+    pub auto_discard_dead: bool,
+    /// deal starting hands like at the table, with each player in seat order taking one tile
+    /// from the shuffled bag per round. otherwise each player takes their whole hand at once.
+    pub deal_one_at_a_time: bool,
+    pub bonus_rounding: BonusRounding,
+    /// who takes the first turn, picked at random when None like drawing tiles for it at the
+    /// table
+    pub starting_player: Option<PlayerId>,
+    /// how many chains can be founded, small boards may not have room for all seven. chains
+    /// are dropped from the end of `CHAIN_ARRAY`, so Imperial is the first to go.
+    pub num_chains: u8,
+    /// go through the stock purchase phase every turn, even when there's nothing to buy and
+    /// passing is the only option
+    pub always_show_purchase_phase: bool,
+    /// how tiles are written when parsing and formatting them for players
+    pub coordinate_style: CoordinateStyle,
+    /// reject any action in `try_apply_action` that leaves more shares of a chain in play than
+    /// the bank started with, which can only happen through an accounting bug
+    pub enforce_stock_cap: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            num_players: 4,
+            num_tiles: 6,
+            grid_width: 12,
+            grid_height: 9,
+            num_stock: StockSupply::default(),
+            starting_money: 6000,
+            safe_chain_size: DEFAULT_SAFE_CHAIN_SIZE,
+            game_ending_chain_size: DEFAULT_GAME_ENDING_CHAIN_SIZE,
+            max_turns: None,
+            max_buys_per_turn: 3,
+            auto_discard_dead: false,
+            deal_one_at_a_time: false,
+            bonus_rounding: BonusRounding::RoundUpEach,
+            starting_player: Some(PlayerId(0)),
+            num_chains: NUM_CHAINS,
+            always_show_purchase_phase: false,
+            coordinate_style: CoordinateStyle::RowLetter,
+            enforce_stock_cap: false,
+        }
+    }
+}
+
+impl Options {
+    /// The game as printed in the rulebook. Only the number of players changes, everything else
+    /// is fixed, and the first player is decided by drawing tiles rather than by seat.
+    pub fn official(num_players: u8) -> Result<Options, OptionsError> {
+        if !(2..=6).contains(&num_players) {
+            return Err(OptionsError::UnofficialPlayerCount(num_players));
+        }
+
+        Ok(Options {
+            num_players,
+            num_tiles: 6,
+            grid_width: 12,
+            grid_height: 9,
+            num_stock: StockSupply::Uniform(25),
+            starting_money: 6000,
+            safe_chain_size: 11,
+            game_ending_chain_size: 41,
+            max_turns: None,
+            max_buys_per_turn: 3,
+            auto_discard_dead: false,
+            deal_one_at_a_time: false,
+            bonus_rounding: BonusRounding::RoundUpEach,
+            starting_player: None,
+            num_chains: 7,
+            always_show_purchase_phase: false,
+            coordinate_style: CoordinateStyle::RowLetter,
+            enforce_stock_cap: false,
+        })
+    }
+
+    /// Checks that a game can actually be dealt and played with these options.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.num_players == 0 {
+            return Err(OptionsError::NoPlayers);
+        }
+
+        if self.num_tiles == 0 {
+            return Err(OptionsError::NoTiles);
+        }
+
+        // rows are lettered A-Z, and so are columns when they're the ones written as letters
+        if self.grid_width == 0 || self.grid_height == 0 || self.grid_height > 26 {
+            return Err(OptionsError::InvalidBoardSize);
+        }
+        if self.coordinate_style == CoordinateStyle::ColumnLetter && self.grid_width > 26 {
+            return Err(OptionsError::InvalidBoardSize);
+        }
+
+        let available = self.grid_width as u16 * self.grid_height as u16;
+        let required = self.num_players as u16 * self.num_tiles as u16;
+        if required > available {
+            return Err(OptionsError::NotEnoughTiles { available, required });
+        }
+
+        if self.num_chains == 0 || self.num_chains > NUM_CHAINS {
+            return Err(OptionsError::InvalidChainCount(self.num_chains));
+        }
+
+        if let Some(PlayerId(starting_player)) = self.starting_player {
+            if starting_player >= self.num_players {
+                return Err(OptionsError::InvalidStartingPlayer { starting_player, num_players: self.num_players });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CoordinateStyle, Options, OptionsError, PlayerId, StockSupply};
+
+    #[test]
+    fn test_official() {
+        for num_players in 2..=6 {
+            let options = Options::official(num_players).unwrap();
+            assert_eq!(options.validate(), Ok(()));
+            assert_eq!(options.num_players, num_players);
+            assert_eq!((options.grid_width, options.grid_height), (12, 9));
+            assert_eq!(options.num_tiles, 6);
+            assert_eq!(options.num_stock, StockSupply::Uniform(25));
+            assert_eq!(options.starting_money, 6000);
+            assert_eq!((options.safe_chain_size, options.game_ending_chain_size), (11, 41));
+            assert_eq!(options.max_buys_per_turn, 3);
+            assert_eq!(options.num_chains, 7);
+            assert_eq!(options.starting_player, None);
+        }
+
+        assert_eq!(Options::official(1).err(), Some(OptionsError::UnofficialPlayerCount(1)));
+        assert_eq!(Options::official(7).err(), Some(OptionsError::UnofficialPlayerCount(7)));
+        assert_eq!(Options::official(0).err(), Some(OptionsError::UnofficialPlayerCount(0)));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Options::default().validate(), Ok(()));
+
+        assert_eq!(Options { num_players: 0, ..Options::default() }.validate(), Err(OptionsError::NoPlayers));
+        assert_eq!(Options { num_tiles: 0, ..Options::default() }.validate(), Err(OptionsError::NoTiles));
+        assert_eq!(Options { grid_height: 27, ..Options::default() }.validate(), Err(OptionsError::InvalidBoardSize));
+        assert_eq!(
+            Options { grid_width: 3, grid_height: 3, ..Options::default() }.validate(),
+            Err(OptionsError::NotEnoughTiles { available: 9, required: 24 })
+        );
+        assert_eq!(
+            Options { starting_player: Some(PlayerId(4)), ..Options::default() }.validate(),
+            Err(OptionsError::InvalidStartingPlayer { starting_player: 4, num_players: 4 })
+        );
+        assert_eq!(Options { starting_player: None, ..Options::default() }.validate(), Ok(()));
+        assert_eq!(Options { num_chains: 0, ..Options::default() }.validate(), Err(OptionsError::InvalidChainCount(0)));
+        assert_eq!(Options { num_chains: 8, ..Options::default() }.validate(), Err(OptionsError::InvalidChainCount(8)));
+        assert_eq!(
+            Options { grid_width: 27, coordinate_style: CoordinateStyle::ColumnLetter, ..Options::default() }.validate(),
+            Err(OptionsError::InvalidBoardSize)
+        );
+        assert_eq!(Options { grid_width: 27, ..Options::default() }.validate(), Ok(()));
+    }
+}
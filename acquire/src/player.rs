@@ -7,5 +7,17 @@ pub struct Player {
     pub id: PlayerId,
     pub tiles: Vec<Tile>,
     pub stocks: Stocks,
-    pub money: u32
+    pub money: u32,
+    pub stats: PlayerStats,
+}
+
+/// Running totals of what a player has done over the game, see `Acquire::player_stats`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerStats {
+    pub tiles_placed: u16,
+    pub shares_bought: u16,
+    /// placements which merged chains, however many chains went defunct
+    pub mergers_initiated: u16,
+    /// majority and minority bonuses, including the ones paid out at the end of the game
+    pub bonuses_received: u32,
 }
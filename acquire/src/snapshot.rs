@@ -0,0 +1,704 @@
+use thiserror::Error;
+use crate::{Acquire, BonusRounding, MergePhase, MergingChains, Options, OptionsError, Phase, PlayerId, StockSupply};
+use crate::chain::{Chain, ChainTable, CHAIN_ARRAY, NUM_CHAINS};
+use crate::grid::{Grid, Legality, Point, Slot};
+use crate::player::{Player, PlayerStats};
+use crate::stock::Stocks;
+use crate::tile::{CoordinateStyle, Tile};
+
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    #[error("the buffer ended before the snapshot was complete")]
+    UnexpectedEnd,
+    #[error("unsupported snapshot version {0}")]
+    UnsupportedVersion(u8),
+    #[error("invalid {what} tag {tag}")]
+    InvalidTag {
+        what: &'static str,
+        tag: u8,
+    },
+    #[error("player {0} does not exist")]
+    InvalidPlayer(u8),
+    #[error("the options can't make a game: {0}")]
+    InvalidOptions(#[from] OptionsError),
+    #[error("there are {actual} players but the options are for {expected}")]
+    PlayerCountMismatch {
+        expected: u8,
+        actual: u8,
+    },
+    #[error("{0} is not on the board")]
+    TileOffBoard(Tile),
+    #[error("there are bytes left over after the snapshot")]
+    TrailingBytes,
+}
+
+/// A compact, dependency free binary encoding of the full game state, intended for
+/// checkpointing many games quickly. Indicators and the game log are not saved.
+///
+/// Layout (little endian): version, options, counters, phase, grid, bag, bank stock, then players.
+impl Acquire {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::with_capacity(256));
+
+        w.u8(FORMAT_VERSION);
+        w.options(&self.options);
+
+        w.u8(self.current_player_id.0);
+        w.u16(self.turn);
+        w.u16(self.step);
+        w.bool(self.terminated);
+
+        w.phase(&self.phase);
+        w.grid(&self.grid);
+
+        w.u16(self.tiles.len() as u16);
+        for tile in &self.tiles {
+            w.point(tile.0);
+        }
+
+        w.stocks(&self.stocks);
+
+        w.u8(self.players.len() as u8);
+        for player in &self.players {
+            w.u32(player.money);
+            w.stocks(&player.stocks);
+            w.u16(player.stats.tiles_placed);
+            w.u16(player.stats.shares_bought);
+            w.u16(player.stats.mergers_initiated);
+            w.u32(player.stats.bonuses_received);
+            w.u8(player.tiles.len() as u8);
+            for tile in &player.tiles {
+                w.point(tile.0);
+            }
+        }
+
+        w.0
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Acquire, DecodeError> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        let version = r.u8()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let options = r.options()?;
+        options.validate()?;
+
+        let current_player_id = PlayerId(r.u8()?);
+        let turn = r.u16()?;
+        let step = r.u16()?;
+        let terminated = r.bool()?;
+
+        let phase = r.phase()?;
+        let grid = r.grid()?;
+
+        let num_tiles = r.u16()?;
+        let mut tiles = Vec::with_capacity(num_tiles as usize);
+        for _ in 0..num_tiles {
+            tiles.push(Tile(r.point()?));
+        }
+
+        let stocks = r.stocks()?;
+
+        let num_players = r.u8()?;
+        let mut players = Vec::with_capacity(num_players as usize);
+        for id in 0..num_players {
+            let money = r.u32()?;
+            let player_stocks = r.stocks()?;
+            let stats = PlayerStats {
+                tiles_placed: r.u16()?,
+                shares_bought: r.u16()?,
+                mergers_initiated: r.u16()?,
+                bonuses_received: r.u32()?,
+            };
+
+            let hand_size = r.u8()?;
+            let mut hand = Vec::with_capacity(hand_size as usize);
+            for _ in 0..hand_size {
+                hand.push(Tile(r.point()?));
+            }
+
+            players.push(Player {
+                id: PlayerId(id),
+                tiles: hand,
+                stocks: player_stocks,
+                money,
+                stats,
+            });
+        }
+
+        if r.pos != bytes.len() {
+            return Err(DecodeError::TrailingBytes);
+        }
+
+        if num_players != options.num_players {
+            return Err(DecodeError::PlayerCountMismatch { expected: options.num_players, actual: num_players });
+        }
+
+        let all_tiles = tiles.iter().chain(players.iter().flat_map(|player| &player.tiles));
+        if let Some(tile) = all_tiles.into_iter().find(|tile| grid.is_pt_out_of_bounds(tile.0)) {
+            return Err(DecodeError::TileOffBoard(*tile));
+        }
+
+        // every player id referenced must be seated, otherwise the game would panic later on
+        let mut referenced_player_ids = vec![current_player_id];
+        if let Phase::Merge { merging_player_id, .. } = &phase {
+            referenced_player_ids.push(*merging_player_id);
+        }
+        if let Some(player_id) = referenced_player_ids.iter().find(|id| id.0 >= num_players) {
+            return Err(DecodeError::InvalidPlayer(player_id.0));
+        }
+
+        Ok(Acquire {
+            phase,
+            players,
+            tiles,
+            stocks,
+            grid,
+            current_player_id,
+            turn,
+            step,
+            terminated,
+            options,
+            log: None,
+            pre_merge: None,
+        })
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn point(&mut self, pt: Point) {
+        self.u8(pt.x as u8);
+        self.u8(pt.y as u8);
+    }
+
+    fn chain(&mut self, chain: Chain) {
+        self.u8(chain.as_index() as u8);
+    }
+
+    fn stocks(&mut self, stocks: &Stocks) {
+        for chain in &CHAIN_ARRAY {
+            self.u8(stocks.amount(*chain));
+        }
+    }
+
+    fn options(&mut self, options: &Options) {
+        self.u8(options.num_players);
+        self.u8(options.num_tiles);
+        self.u8(options.grid_width);
+        self.u8(options.grid_height);
+        match &options.num_stock {
+            StockSupply::Uniform(amount) => {
+                self.u8(0);
+                self.u8(*amount);
+            }
+            StockSupply::PerChain(amounts) => {
+                self.u8(1);
+                for chain in &CHAIN_ARRAY {
+                    self.u8(amounts.get(chain));
+                }
+            }
+        }
+        self.u32(options.starting_money);
+        self.u16(options.safe_chain_size);
+        self.u16(options.game_ending_chain_size);
+
+        match options.max_turns {
+            None => self.bool(false),
+            Some(max_turns) => {
+                self.bool(true);
+                self.u16(max_turns);
+            }
+        }
+
+        self.u8(options.max_buys_per_turn);
+        self.bool(options.auto_discard_dead);
+        self.bool(options.deal_one_at_a_time);
+        self.u8(match options.bonus_rounding {
+            BonusRounding::RoundUpEach => 0,
+            BonusRounding::RoundDownEach => 1,
+            BonusRounding::RemainderToMajority => 2,
+        });
+
+        match options.starting_player {
+            None => self.bool(false),
+            Some(starting_player) => {
+                self.bool(true);
+                self.u8(starting_player.0);
+            }
+        }
+
+        self.u8(options.num_chains);
+        self.bool(options.always_show_purchase_phase);
+        self.u8(match options.coordinate_style {
+            CoordinateStyle::RowLetter => 0,
+            CoordinateStyle::ColumnLetter => 1,
+        });
+        self.bool(options.enforce_stock_cap);
+    }
+
+    fn phase(&mut self, phase: &Phase) {
+        match phase {
+            Phase::AwaitingTilePlacement => self.u8(0),
+            Phase::AwaitingChainCreationSelection => self.u8(1),
+            Phase::AwaitingStockPurchase => self.u8(2),
+            Phase::AwaitingGameTerminationDecision => self.u8(3),
+            Phase::Merge { merging_player_id, phase, mergers_remaining } => {
+                self.u8(4);
+                self.u8(merging_player_id.0);
+
+                match phase {
+                    MergePhase::AwaitingTiebreakSelection { tied_chains } => {
+                        self.u8(0);
+                        self.u8(tied_chains.len() as u8);
+                        for chain in tied_chains {
+                            self.chain(*chain);
+                        }
+                    }
+                    MergePhase::AwaitingMergeDecision => self.u8(1),
+                }
+
+                self.u8(mergers_remaining.len() as u8);
+                for merger in mergers_remaining {
+                    self.chain(merger.merging_chain);
+                    self.chain(merger.defunct_chain);
+                    match merger.num_remaining_players_to_merge {
+                        None => self.bool(false),
+                        Some(num) => {
+                            self.bool(true);
+                            self.u8(num);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn grid(&mut self, grid: &Grid) {
+        self.u8(grid.width);
+        self.u8(grid.height);
+        self.u16(grid.safe_chain_size);
+        self.u16(grid.game_ending_chain_size);
+        self.u8(grid.num_chains);
+
+        match grid.previously_placed_tile_pt {
+            None => self.bool(false),
+            Some(pt) => {
+                self.bool(true);
+                self.point(pt);
+            }
+        }
+
+        // the map has no stable iteration order, sort so equal grids encode identically
+        let mut slots: Vec<(&Point, &Slot)> = grid.data.iter().collect();
+        slots.sort_by_key(|(pt, _)| **pt);
+
+        self.u16(slots.len() as u16);
+        for (pt, slot) in slots {
+            self.point(*pt);
+            match slot {
+                Slot::Empty(Legality::Legal) => self.u8(0),
+                Slot::Empty(Legality::TemporarilyIllegal) => self.u8(1),
+                Slot::Empty(Legality::PermanentIllegal) => self.u8(2),
+                Slot::NoChain => self.u8(3),
+                Slot::Limbo => self.u8(4),
+                Slot::Chain(chain) => self.u8(5 + chain.as_index() as u8),
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let end = self.pos + N;
+        let Some(slice) = self.bytes.get(self.pos..end) else {
+            return Err(DecodeError::UnexpectedEnd);
+        };
+
+        self.pos = end;
+        Ok(slice.try_into().expect("a slice of length N"))
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, DecodeError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(DecodeError::InvalidTag { what: "bool", tag }),
+        }
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn point(&mut self) -> Result<Point, DecodeError> {
+        Ok(Point {
+            x: self.u8()? as i8,
+            y: self.u8()? as i8,
+        })
+    }
+
+    fn chain(&mut self) -> Result<Chain, DecodeError> {
+        let tag = self.u8()?;
+        if tag as usize >= CHAIN_ARRAY.len() {
+            return Err(DecodeError::InvalidTag { what: "chain", tag });
+        }
+
+        Ok(Chain::from_index(tag as usize))
+    }
+
+    fn num_chains(&mut self) -> Result<u8, DecodeError> {
+        let tag = self.u8()?;
+        if tag == 0 || tag > NUM_CHAINS {
+            return Err(DecodeError::InvalidTag { what: "chain count", tag });
+        }
+
+        Ok(tag)
+    }
+
+    fn stocks(&mut self) -> Result<Stocks, DecodeError> {
+        let mut stocks = Stocks::new(0);
+        for chain in &CHAIN_ARRAY {
+            stocks.deposit(*chain, self.u8()?);
+        }
+
+        Ok(stocks)
+    }
+
+    fn options(&mut self) -> Result<Options, DecodeError> {
+        Ok(Options {
+            num_players: self.u8()?,
+            num_tiles: self.u8()?,
+            grid_width: self.u8()?,
+            grid_height: self.u8()?,
+            num_stock: match self.u8()? {
+                0 => StockSupply::Uniform(self.u8()?),
+                1 => {
+                    let mut amounts: ChainTable<u8> = ChainTable::default();
+                    for chain in &CHAIN_ARRAY {
+                        amounts.set(chain, self.u8()?);
+                    }
+                    StockSupply::PerChain(amounts)
+                }
+                tag => return Err(DecodeError::InvalidTag { what: "stock supply", tag }),
+            },
+            starting_money: self.u32()?,
+            safe_chain_size: self.u16()?,
+            game_ending_chain_size: self.u16()?,
+            max_turns: if self.bool()? { Some(self.u16()?) } else { None },
+            max_buys_per_turn: self.u8()?,
+            auto_discard_dead: self.bool()?,
+            deal_one_at_a_time: self.bool()?,
+            bonus_rounding: match self.u8()? {
+                0 => BonusRounding::RoundUpEach,
+                1 => BonusRounding::RoundDownEach,
+                2 => BonusRounding::RemainderToMajority,
+                tag => return Err(DecodeError::InvalidTag { what: "bonus rounding", tag }),
+            },
+            starting_player: if self.bool()? { Some(PlayerId(self.u8()?)) } else { None },
+            num_chains: self.num_chains()?,
+            always_show_purchase_phase: self.bool()?,
+            coordinate_style: match self.u8()? {
+                0 => CoordinateStyle::RowLetter,
+                1 => CoordinateStyle::ColumnLetter,
+                tag => return Err(DecodeError::InvalidTag { what: "coordinate style", tag }),
+            },
+            enforce_stock_cap: self.bool()?,
+        })
+    }
+
+    fn phase(&mut self) -> Result<Phase, DecodeError> {
+        let phase = match self.u8()? {
+            0 => Phase::AwaitingTilePlacement,
+            1 => Phase::AwaitingChainCreationSelection,
+            2 => Phase::AwaitingStockPurchase,
+            3 => Phase::AwaitingGameTerminationDecision,
+            4 => {
+                let merging_player_id = PlayerId(self.u8()?);
+
+                let phase = match self.u8()? {
+                    0 => {
+                        let num_tied_chains = self.u8()?;
+                        let mut tied_chains = vec![];
+                        for _ in 0..num_tied_chains {
+                            tied_chains.push(self.chain()?);
+                        }
+
+                        MergePhase::AwaitingTiebreakSelection { tied_chains }
+                    }
+                    1 => MergePhase::AwaitingMergeDecision,
+                    tag => return Err(DecodeError::InvalidTag { what: "merge phase", tag }),
+                };
+
+                let num_mergers = self.u8()?;
+                let mut mergers_remaining = vec![];
+                for _ in 0..num_mergers {
+                    let merging_chain = self.chain()?;
+                    let defunct_chain = self.chain()?;
+                    let num_remaining_players_to_merge = if self.bool()? {
+                        Some(self.u8()?)
+                    } else {
+                        None
+                    };
+
+                    mergers_remaining.push(MergingChains {
+                        merging_chain,
+                        defunct_chain,
+                        num_remaining_players_to_merge,
+                    });
+                }
+
+                Phase::Merge {
+                    merging_player_id,
+                    phase,
+                    mergers_remaining,
+                }
+            }
+            tag => return Err(DecodeError::InvalidTag { what: "phase", tag }),
+        };
+
+        Ok(phase)
+    }
+
+    fn grid(&mut self) -> Result<Grid, DecodeError> {
+        let mut grid = Grid::new(self.u8()?, self.u8()?);
+        grid.safe_chain_size = self.u16()?;
+        grid.game_ending_chain_size = self.u16()?;
+        grid.num_chains = self.num_chains()?;
+
+        if self.bool()? {
+            grid.previously_placed_tile_pt = Some(self.point()?);
+        }
+
+        let num_slots = self.u16()?;
+        for _ in 0..num_slots {
+            let pt = self.point()?;
+            let slot = match self.u8()? {
+                0 => Slot::Empty(Legality::Legal),
+                1 => Slot::Empty(Legality::TemporarilyIllegal),
+                2 => Slot::Empty(Legality::PermanentIllegal),
+                3 => Slot::NoChain,
+                4 => Slot::Limbo,
+                tag @ 5..=11 => Slot::Chain(Chain::from_index((tag - 5) as usize)),
+                tag => return Err(DecodeError::InvalidTag { what: "slot", tag }),
+            };
+
+            grid.data.insert(pt, slot);
+        }
+
+        grid.recount_chain_sizes();
+
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+    use rand::seq::SliceRandom;
+    use crate::{Acquire, Action, Chain, MergePhase, Options, OptionsError, Phase, PlayerId, Tile, tile};
+    use crate::snapshot::DecodeError;
+
+    fn assert_round_trip(game: &Acquire) {
+        let bytes = game.to_bytes();
+        let decoded = Acquire::from_bytes(&bytes).expect("a valid snapshot");
+
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.actions(), game.actions());
+        assert_eq!(decoded.to_string(), game.to_string());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for n in 0..10 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(n);
+            let mut game = Acquire::new(&mut rng, &Options::default());
+
+            loop {
+                assert_round_trip(&game);
+
+                if game.is_terminated() {
+                    break;
+                }
+
+                let actions = game.actions();
+                let action = actions.choose(&mut rng).expect("an action");
+
+                game = game.apply_action(action.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn test_resume_mid_merge() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+
+        game.grid.place(tile!("D1"));
+        game.grid.place(tile!("D2"));
+        game.grid.fill_chain(tile!("D2"), Chain::American);
+
+        game.grid.place(tile!("D4"));
+        game.grid.place(tile!("D5"));
+        game.grid.fill_chain(tile!("D5"), Chain::Festival);
+
+        game.grid.place(tile!("B3"));
+        game.grid.place(tile!("C3"));
+        game.grid.fill_chain(tile!("C3"), Chain::Continental);
+
+        game.grid.place(tile!("E3"));
+        game.grid.place(tile!("F3"));
+        game.grid.fill_chain(tile!("F3"), Chain::Tower);
+
+        for (idx, player) in game.players.iter_mut().enumerate() {
+            player.stocks.deposit(Chain::American, 1 + idx as u8);
+            player.stocks.deposit(Chain::Festival, 2);
+            player.stocks.deposit(Chain::Continental, 3 - idx as u8);
+        }
+
+        game.players[0].tiles[0] = tile!("D3");
+        game = game.apply_action(Action::PlaceTile(PlayerId(0), tile!("D3")));
+
+        // pick the survivor, then let the first couple of players decide
+        game = game.apply_action(game.actions().remove(0));
+        game = game.apply_action(game.actions().remove(1));
+        game = game.apply_action(game.actions().remove(0));
+
+        assert!(matches!(game.phase, Phase::Merge { phase: MergePhase::AwaitingMergeDecision, .. }));
+
+        let mut resumed = Acquire::from_bytes(&game.to_bytes()).expect("a valid snapshot");
+        assert_eq!(resumed.phase, game.phase);
+
+        while matches!(game.phase, Phase::Merge { .. }) {
+            let actions = game.actions();
+            assert_eq!(resumed.actions(), actions);
+
+            let action = actions.last().expect("an action").clone();
+            game = game.apply_action(action.clone());
+            resumed = resumed.apply_action(action);
+
+            assert_eq!(resumed.to_bytes(), game.to_bytes());
+        }
+
+        assert_eq!(resumed.phase, Phase::AwaitingStockPurchase);
+    }
+
+    #[test]
+    fn test_truncated_buffers() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let mut game = Acquire::new(&mut rng, &Options::default());
+        for _ in 0..40 {
+            let actions = game.actions();
+            game = game.apply_action(actions.choose(&mut rng).expect("an action").clone());
+        }
+
+        let bytes = game.to_bytes();
+        for len in 0..bytes.len() {
+            assert_eq!(Acquire::from_bytes(&bytes[..len]).err(), Some(DecodeError::UnexpectedEnd));
+        }
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(Acquire::from_bytes(&extended).err(), Some(DecodeError::TrailingBytes));
+    }
+
+    #[test]
+    fn test_invalid_chain_count() {
+        let game = Acquire::from_seed(2, &Options::default());
+
+        for num_chains in [0, 8] {
+            let mut options_only = game.clone();
+            options_only.options.num_chains = num_chains;
+            assert_eq!(
+                Acquire::from_bytes(&options_only.to_bytes()).err(),
+                Some(DecodeError::InvalidTag { what: "chain count", tag: num_chains })
+            );
+
+            let mut grid_only = game.clone();
+            grid_only.grid.num_chains = num_chains;
+            assert_eq!(
+                Acquire::from_bytes(&grid_only.to_bytes()).err(),
+                Some(DecodeError::InvalidTag { what: "chain count", tag: num_chains })
+            );
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_state() {
+        let game = Acquire::from_seed(2, &Options::default());
+
+        let mut invalid_options = game.clone();
+        invalid_options.options.num_tiles = 0;
+        assert_eq!(
+            Acquire::from_bytes(&invalid_options.to_bytes()).err(),
+            Some(DecodeError::InvalidOptions(OptionsError::NoTiles))
+        );
+
+        let mut missing_player = game.clone();
+        missing_player.players.pop();
+        assert_eq!(
+            Acquire::from_bytes(&missing_player.to_bytes()).err(),
+            Some(DecodeError::PlayerCountMismatch { expected: 4, actual: 3 })
+        );
+
+        let off_board = Tile::new(12, 0);
+        let mut in_hand = game.clone();
+        in_hand.players[2].tiles[0] = off_board;
+        assert_eq!(Acquire::from_bytes(&in_hand.to_bytes()).err(), Some(DecodeError::TileOffBoard(off_board)));
+
+        let mut in_bag = game.clone();
+        in_bag.tiles[0] = off_board;
+        assert_eq!(Acquire::from_bytes(&in_bag.to_bytes()).err(), Some(DecodeError::TileOffBoard(off_board)));
+    }
+
+    #[test]
+    fn test_garbage_does_not_panic() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let bytes = Acquire::new(&mut rng, &Options::default()).to_bytes();
+
+        for _ in 0..1000 {
+            let mut corrupted = bytes.clone();
+            for _ in 0..4 {
+                let idx = rng.gen_range(0..corrupted.len());
+                corrupted[idx] = rng.gen();
+            }
+
+            let _ = Acquire::from_bytes(&corrupted);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use thiserror::Error;
-use crate::chain::{Chain, ChainTable};
+use crate::chain::{Chain, ChainTable, CHAIN_ARRAY};
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stocks {
     stocks: ChainTable<u8>,
 }
@@ -20,6 +21,12 @@ impl Stocks {
         }
     }
 
+    pub fn from_amounts(amounts: ChainTable<u8>) -> Self {
+        Self {
+            stocks: amounts
+        }
+    }
+
     pub fn amount(&self, chain: Chain) -> u8 {
         self.stocks.get(&chain)
     }
@@ -32,6 +39,21 @@ impl Stocks {
         self.stocks[&chain] >= amount
     }
 
+    /// The number of shares held across every chain.
+    pub fn total(&self) -> u16 {
+        self.stocks.0.iter().map(|amount| *amount as u16).sum()
+    }
+
+    /// Every chain with at least one share, along with how many, in `CHAIN_ARRAY` order.
+    pub fn iter(&self) -> impl Iterator<Item = (Chain, u8)> + '_ {
+        self.iter_all().filter(|(_, amount)| *amount > 0)
+    }
+
+    /// Like `iter`, but includes the chains with no shares.
+    pub fn iter_all(&self) -> impl Iterator<Item = (Chain, u8)> + '_ {
+        CHAIN_ARRAY.iter().map(|chain| (*chain, self.amount(*chain)))
+    }
+
     pub fn deposit(&mut self, chain: Chain, amount: u8) {
         if amount == 0 {
             return;
@@ -52,4 +74,28 @@ impl Stocks {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::chain::Chain;
+    use crate::stock::Stocks;
+
+    #[test]
+    fn test_iter() {
+        let mut stocks = Stocks::new(0);
+        stocks.deposit(Chain::Imperial, 2);
+        stocks.deposit(Chain::Tower, 5);
+        stocks.deposit(Chain::American, 1);
+        stocks.withdraw(Chain::American, 1).unwrap();
+
+        assert_eq!(stocks.iter().collect::<Vec<_>>(), vec![(Chain::Tower, 5), (Chain::Imperial, 2)]);
+
+        let all: Vec<(Chain, u8)> = stocks.iter_all().collect();
+        assert_eq!(all.len(), 7);
+        assert_eq!(all[0], (Chain::Tower, 5));
+        assert!(all.contains(&(Chain::American, 0)));
+
+        assert_eq!(stocks.total(), 7);
+    }
+}
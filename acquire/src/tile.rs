@@ -14,32 +14,74 @@ pub enum TileParseError {
     InvalidNumber,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+/// Which of a tile's coordinates is written as a letter.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CoordinateStyle {
+    /// rows are lettered A-I and columns numbered 1-12, e.g. "H11"
+    #[default]
+    RowLetter,
+    /// columns are lettered A-L and rows numbered 1-9, so the same cell as "H11" is "K8"
+    ColumnLetter,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile(pub Point);
 
 impl Tile {
     pub fn new(x: i8, y: i8) -> Self {
         Self(Point { x, y })
     }
-}
 
-impl TryFrom<&str> for Tile {
-    type Error = TileParseError;
+    /// Every tile of a `width` x `height` board, row by row.
+    pub fn all_for_board(width: u8, height: u8) -> impl Iterator<Item = Tile> {
+        (0..height as i8).flat_map(move |y| (0..width as i8).map(move |x| Tile::new(x, y)))
+    }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+    pub fn parse(value: &str, style: CoordinateStyle) -> Result<Tile, TileParseError> {
         if value.len() < 2 || value.len() > 3 {
             return Err(TileParseError::WrongLength);
         }
 
-        let Ok(y) = map_letter_to_i8(value.chars().nth(0).unwrap()) else {
+        let Ok(letter) = map_letter_to_i8(value.chars().nth(0).unwrap()) else {
             return Err(TileParseError::InvalidLetter);
         };
 
-        let Ok(x) = i8::from_str(&value[1..]) else {
+        let Ok(number) = i8::from_str(&value[1..]) else {
             return Err(TileParseError::InvalidNumber);
         };
 
-        Ok(Tile::new(x - 1, y - 1))
+        match style {
+            CoordinateStyle::RowLetter => Ok(Tile::new(number - 1, letter - 1)),
+            CoordinateStyle::ColumnLetter => Ok(Tile::new(letter - 1, number - 1)),
+        }
+    }
+
+    pub fn format(&self, style: CoordinateStyle) -> String {
+        let (letter, number) = match style {
+            CoordinateStyle::RowLetter => (self.0.y, self.0.x),
+            CoordinateStyle::ColumnLetter => (self.0.x, self.0.y),
+        };
+
+        match map_i8_to_letter(letter + 1) {
+            Ok(letter) => format!("{}{}", letter, number + 1),
+            Err(_) => format!("?{}", number + 1),
+        }
+    }
+
+    pub fn is_on_board(&self, width: u8, height: u8) -> bool {
+        self.0.x >= 0 &&
+            self.0.y >= 0 &&
+            (self.0.x as i16) < width as i16 &&
+            (self.0.y as i16) < height as i16
+    }
+}
+
+impl TryFrom<&str> for Tile {
+    type Error = TileParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Tile::parse(value, CoordinateStyle::RowLetter)
     }
 }
 
@@ -51,11 +93,7 @@ impl Debug for Tile {
 
 impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Ok(y) = map_i8_to_letter(self.0.y + 1) {
-            f.write_fmt(format_args!("{}{}", y, self.0.x + 1))
-        } else {
-            f.write_fmt(format_args!("?{}", self.0.x + 1))
-        }
+        f.write_str(&self.format(CoordinateStyle::RowLetter))
     }
 }
 
@@ -88,7 +126,8 @@ macro_rules! tile {
 
 #[cfg(test)]
 mod test {
-    use crate::tile::{map_i8_to_letter, map_letter_to_i8, Tile};
+    use itertools::Itertools;
+    use crate::tile::{map_i8_to_letter, map_letter_to_i8, CoordinateStyle, Tile};
 
     #[test]
     fn test_map_letter() {
@@ -125,4 +164,51 @@ mod test {
         let tile: Tile = "Z99".try_into().unwrap();
         assert_eq!("Z99", tile.to_string().as_str());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_coordinate_styles() {
+        let tile = Tile::new(10, 7);
+        assert_eq!(tile.format(CoordinateStyle::RowLetter), "H11");
+        assert_eq!(tile.format(CoordinateStyle::ColumnLetter), "K8");
+        assert_eq!(Tile::parse("K8", CoordinateStyle::ColumnLetter).unwrap(), tile);
+
+        for style in [CoordinateStyle::RowLetter, CoordinateStyle::ColumnLetter] {
+            for tile in Tile::all_for_board(12, 9) {
+                assert_eq!(Tile::parse(&tile.format(style), style).unwrap(), tile);
+            }
+        }
+
+        // the row letter style is what the rest of the crate uses
+        assert_eq!(Tile::parse("B10", CoordinateStyle::RowLetter).unwrap(), "B10".try_into().unwrap());
+        assert_eq!(Tile::parse("L9", CoordinateStyle::ColumnLetter).unwrap(), Tile::new(11, 8));
+    }
+
+    #[test]
+    fn test_all_for_board() {
+        let tiles: Vec<Tile> = Tile::all_for_board(12, 9).collect();
+
+        assert_eq!(tiles.len(), 12 * 9);
+        assert_eq!(tiles.iter().unique().count(), 12 * 9);
+        assert!(tiles.iter().all(|tile| tile.is_on_board(12, 9)));
+
+        assert_eq!(tiles[0], "A1".try_into().unwrap());
+        assert_eq!(tiles[12 * 9 - 1], "I12".try_into().unwrap());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut tiles: Vec<Tile> = vec![tile!("B1"), tile!("A12"), tile!("A2"), tile!("I1"), tile!("A1")];
+        tiles.sort();
+
+        assert_eq!(tiles, vec![tile!("A1"), tile!("A2"), tile!("A12"), tile!("B1"), tile!("I1")]);
+    }
+
+    #[test]
+    fn test_is_on_board() {
+        assert!(Tile::new(0, 0).is_on_board(12, 9));
+        assert!(Tile::new(11, 8).is_on_board(12, 9));
+        assert!(!Tile::new(12, 8).is_on_board(12, 9));
+        assert!(!Tile::new(11, 9).is_on_board(12, 9));
+        assert!(!Tile::new(-1, 0).is_on_board(12, 9));
+    }
+}
@@ -0,0 +1,113 @@
+use bg_ai::{Outcome, State};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use crate::{Acquire, Action, Options, PlayerId};
+
+/// Anything that can pick an action for the player whose decision it currently is.
+pub trait Agent {
+    fn select_action(&mut self, rng: &mut ChaCha8Rng, state: &Acquire, actions: &[Action]) -> Action;
+}
+
+/// Picks uniformly from the available actions, useful as a baseline opponent.
+pub struct RandomAgent;
+
+impl Agent for RandomAgent {
+    fn select_action(&mut self, rng: &mut ChaCha8Rng, _: &Acquire, actions: &[Action]) -> Action {
+        actions.choose(rng).expect("an action").clone()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PlayerMatchStats {
+    pub player_id: PlayerId,
+    pub money: u32,
+    pub shares_held: u16,
+    pub actions_taken: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchResult {
+    pub winners: Vec<PlayerId>,
+    pub num_turns: u16,
+    pub num_steps: u32,
+    pub player_stats: Vec<PlayerMatchStats>,
+}
+
+/// Plays a single game to completion without any I/O.
+///
+/// `agents` holds one agent per seat, in player id order. The same seed always produces the same
+/// game, as long as the agents themselves are deterministic.
+pub fn run_match(options: &Options, agents: &mut [Box<dyn Agent>], seed: u64) -> MatchResult {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut game = Acquire::new(&mut rng, options);
+
+    assert_eq!(agents.len(), game.players.len(), "there must be exactly one agent per player");
+
+    let mut actions_taken = vec![0u32; agents.len()];
+    let mut num_steps = 0;
+
+    let outcome = loop {
+        if let Some(outcome) = State::outcome(&game) {
+            break outcome;
+        }
+
+        let player_id = State::current_player(&game);
+        let actions = game.actions();
+        let action = agents[player_id.0 as usize].select_action(&mut rng, &game, &actions);
+
+        game = game.apply_action(action);
+        actions_taken[player_id.0 as usize] += 1;
+        num_steps += 1;
+    };
+
+    let winners = match outcome {
+        Outcome::Winner(player_id) => vec![player_id],
+        Outcome::Draw(player_ids) => player_ids,
+    };
+
+    let player_stats = game.players
+        .iter()
+        .map(|player| PlayerMatchStats {
+            player_id: player.id,
+            money: player.money,
+            shares_held: player.stocks.total(),
+            actions_taken: actions_taken[player.id.0 as usize],
+        })
+        .collect();
+
+    MatchResult {
+        winners,
+        num_turns: game.turn,
+        num_steps,
+        player_stats,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Options;
+    use crate::tournament::{Agent, RandomAgent, run_match};
+
+    fn random_agents(n: usize) -> Vec<Box<dyn Agent>> {
+        (0..n).map(|_| Box::new(RandomAgent) as Box<dyn Agent>).collect()
+    }
+
+    #[test]
+    fn test_run_match() {
+        let result = run_match(&Options::default(), &mut random_agents(4), 2);
+
+        assert!(!result.winners.is_empty());
+        assert!(result.num_turns > 1);
+        assert_eq!(result.player_stats.len(), 4);
+        assert_eq!(result.num_steps, result.player_stats.iter().map(|s| s.actions_taken).sum::<u32>());
+
+        let most_money = result.player_stats.iter().map(|s| s.money).max().unwrap();
+        for winner in &result.winners {
+            assert_eq!(result.player_stats[winner.0 as usize].money, most_money);
+        }
+
+        // same seed, same agents, same game
+        assert_eq!(result, run_match(&Options::default(), &mut random_agents(4), 2));
+    }
+}
@@ -0,0 +1,137 @@
+use thiserror::Error;
+use crate::{Acquire, AcquireError, Action, Options, OptionsError};
+use crate::chain::{Chain, CHAIN_ARRAY};
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum TranscriptError {
+    #[error("the game can't be set up: {0}")]
+    InvalidOptions(#[from] OptionsError),
+    #[error("action {step} is illegal: {error}")]
+    IllegalAction {
+        step: usize,
+        error: AcquireError,
+    },
+    #[error("the state after action {step} is inconsistent: {inconsistency}")]
+    Inconsistent {
+        step: usize,
+        inconsistency: Inconsistency,
+    },
+}
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum Inconsistency {
+    /// only checked in debug builds
+    #[error("the tracked chain sizes don't match the board")]
+    ChainSizes,
+    #[error("there are {actual} shares of {chain} in play instead of {expected}")]
+    StockNotConserved {
+        chain: Chain,
+        expected: u16,
+        actual: u16,
+    },
+}
+
+impl Acquire {
+    /// Replays `actions` from a freshly dealt game, checking each is legal and that nothing has
+    /// gone missing from the state after it's applied. Errors carry the index of the first bad
+    /// action.
+    pub fn validate_transcript(seed: u64, options: &Options, actions: &[Action]) -> Result<(), TranscriptError> {
+        options.validate()?;
+
+        let mut game = Acquire::from_seed(seed, options);
+        for (step, action) in actions.iter().enumerate() {
+            game = game.try_apply_action(action.clone())
+                .map_err(|error| TranscriptError::IllegalAction { step, error })?;
+
+            game.check_consistency()
+                .map_err(|inconsistency| TranscriptError::Inconsistent { step, inconsistency })?;
+        }
+
+        Ok(())
+    }
+
+    fn check_consistency(&self) -> Result<(), Inconsistency> {
+        #[cfg(debug_assertions)]
+        if !self.grid.verify_chain_sizes() {
+            return Err(Inconsistency::ChainSizes);
+        }
+
+        // shares only ever move between the bank and the players
+        for chain in CHAIN_ARRAY {
+            let expected = self.options.num_stock.amount(chain) as u16;
+            let actual = self.shares_in_play(chain);
+
+            if actual != expected {
+                return Err(Inconsistency::StockNotConserved { chain, expected, actual });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
+    use crate::{Acquire, AcquireError, Action, Options, OptionsError, PlayerId};
+    use crate::transcript::TranscriptError;
+
+    fn random_transcript(seed: u64, options: &Options, num_actions: usize) -> Vec<Action> {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        let mut game = Acquire::from_seed(seed, options);
+        let mut actions = vec![];
+
+        while actions.len() < num_actions && !game.is_terminated() {
+            let action = game.actions().choose(&mut rng).expect("an action").clone();
+            game = game.apply_action(action.clone());
+            actions.push(action);
+        }
+
+        actions
+    }
+
+    #[test]
+    fn test_valid_transcript() {
+        let options = Options::default();
+        for seed in 0..5 {
+            let actions = random_transcript(seed, &options, 300);
+            assert_eq!(Acquire::validate_transcript(seed, &options, &actions), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_illegal_action() {
+        let options = Options::default();
+        let mut actions = random_transcript(2, &options, 20);
+
+        // player 0 takes the first turn, so player 1 placing a tile is out of turn
+        let Action::PlaceTile(_, tile) = actions[0] else {
+            panic!("the game starts with a tile placement");
+        };
+        actions[0] = Action::PlaceTile(PlayerId(1), tile);
+
+        assert_eq!(
+            Acquire::validate_transcript(2, &options, &actions),
+            Err(TranscriptError::IllegalAction {
+                step: 0,
+                error: AcquireError::WrongPlayer { expected: PlayerId(0), actual: PlayerId(1) },
+            })
+        );
+
+        // the same action again later on, once it's been played
+        let mut actions = random_transcript(2, &options, 20);
+        let repeated = actions[0].clone();
+        actions.insert(8, repeated);
+        assert!(matches!(
+            Acquire::validate_transcript(2, &options, &actions),
+            Err(TranscriptError::IllegalAction { step: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let options = Options { num_players: 0, ..Options::default() };
+        assert_eq!(Acquire::validate_transcript(2, &options, &[]), Err(TranscriptError::InvalidOptions(OptionsError::NoPlayers)));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{Acquire, Phase, PlayerId};
+use crate::chain::Chain;
+use crate::grid::{Grid, Legality, Point, Slot};
+use crate::stock::Stocks;
+use crate::tile::Tile;
+
+/// What everyone at the table can see about a player: everything except their tiles.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PublicPlayer {
+    pub id: PlayerId,
+    pub money: u32,
+    pub stocks: Stocks,
+    pub num_tiles: usize,
+}
+
+/// The game as seen by a single player, their own hand is included but the other players'
+/// hands and the order of the tile bag are not.
+#[derive(Clone)]
+pub struct PlayerView {
+    pub player_id: PlayerId,
+    pub hand: Vec<Tile>,
+    pub players: Vec<PublicPlayer>,
+    pub grid: Grid,
+    pub bank: Stocks,
+    pub num_tiles_remaining: usize,
+    pub current_player_id: PlayerId,
+    pub turn: u16,
+}
+
+/// The game as seen by someone who isn't playing, nobody's hand and not the tile bag. Meant to
+/// be broadcast, so the board is flattened row by row instead of keyed by point.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpectatorState {
+    pub width: u8,
+    pub height: u8,
+    pub board: Vec<Slot>,
+    pub players: Vec<PublicPlayer>,
+    pub bank: Stocks,
+    pub num_tiles_remaining: usize,
+    pub current_player_id: PlayerId,
+    pub phase: Phase,
+    pub turn: u16,
+    pub terminated: bool,
+}
+
+/// What's on a single cell of the board, see `BoardCell`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CellKind {
+    Empty(Legality),
+    /// a placed tile which isn't part of a chain
+    NoChain,
+    /// the tile that set off the decision in progress, waiting to join a chain
+    Limbo,
+    Chain(Chain),
+}
+
+/// A single cell of the board with everything needed to draw it. Only chain cells have a colour
+/// and a label, which is the chain's initial.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoardCell {
+    pub point: Point,
+    pub kind: CellKind,
+    pub color: Option<(u8, u8, u8)>,
+    pub label: Option<char>,
+}
+
+impl SpectatorState {
+    pub fn slot(&self, pt: Point) -> Slot {
+        self.board[pt.y as usize * self.width as usize + pt.x as usize]
+    }
+}
+
+impl Acquire {
+    pub fn view_for(&self, player_id: PlayerId) -> PlayerView {
+        PlayerView {
+            player_id,
+            hand: self.get_player_by_id(player_id).tiles.clone(),
+            players: self.public_players(),
+            grid: self.grid.clone(),
+            bank: self.stocks.clone(),
+            num_tiles_remaining: self.tiles.len(),
+            current_player_id: self.current_player_id,
+            turn: self.turn,
+        }
+    }
+
+    pub fn spectator_view(&self) -> SpectatorState {
+        let board = Tile::all_for_board(self.grid.width, self.grid.height)
+            .map(|tile| self.grid.get(tile.0))
+            .collect();
+
+        SpectatorState {
+            width: self.grid.width,
+            height: self.grid.height,
+            board,
+            players: self.public_players(),
+            bank: self.stocks.clone(),
+            num_tiles_remaining: self.tiles.len(),
+            current_player_id: self.current_player_id,
+            phase: self.phase.clone(),
+            turn: self.turn,
+            terminated: self.terminated,
+        }
+    }
+
+    /// Every cell of the board row by row, for drawing it somewhere other than a terminal.
+    pub fn board_cells(&self) -> Vec<BoardCell> {
+        Tile::all_for_board(self.grid.width, self.grid.height).map(|tile| {
+            let kind = match self.grid.get(tile.0) {
+                Slot::Empty(legality) => CellKind::Empty(legality),
+                Slot::NoChain => CellKind::NoChain,
+                Slot::Limbo => CellKind::Limbo,
+                Slot::Chain(chain) => CellKind::Chain(chain),
+            };
+
+            let chain = match kind {
+                CellKind::Chain(chain) => Some(chain),
+                _ => None,
+            };
+
+            BoardCell {
+                point: tile.0,
+                kind,
+                color: chain.map(|chain| chain.color()),
+                label: chain.map(|chain| chain.initial()),
+            }
+        }).collect()
+    }
+
+    fn public_players(&self) -> Vec<PublicPlayer> {
+        self.players.iter().map(|player| PublicPlayer {
+            id: player.id,
+            money: player.money,
+            stocks: player.stocks.clone(),
+            num_tiles: player.tiles.len(),
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use crate::{Acquire, Action, Chain, Options, PlayerId, tile};
+    use crate::grid::{Legality, Slot};
+    use crate::tile::Tile;
+    use crate::view::CellKind;
+
+    #[test]
+    fn test_view_for() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let game = Acquire::new(&mut rng, &Options::default());
+
+        let view = game.view_for(PlayerId(1));
+
+        assert_eq!(view.player_id, PlayerId(1));
+        assert_eq!(view.hand, game.players[1].tiles);
+        assert_eq!(view.num_tiles_remaining, game.tiles.len());
+
+        // everybody else's hand is only visible as a count
+        assert_eq!(view.players.len(), 4);
+        for player in &view.players {
+            assert_eq!(player.num_tiles, 6);
+            assert_eq!(player.money, 6000);
+        }
+    }
+
+    #[test]
+    fn test_spectator_view() {
+        let game = Acquire::from_seed(2, &Options::default());
+        let tile = game.players[0].tiles[0];
+        let game = game.apply_action(Action::PlaceTile(PlayerId(0), tile));
+
+        let view = game.spectator_view();
+
+        assert_eq!(view.board.len(), 12 * 9);
+        assert_eq!(view.slot(tile.0), Slot::NoChain);
+        assert_eq!(view.slot(tile!("I12")), game.grid.get(tile!("I12")));
+        assert_eq!(view.num_tiles_remaining, game.tiles.len());
+
+        for (public, player) in view.players.iter().zip(&game.players) {
+            assert_eq!(public.num_tiles, player.tiles.len());
+        }
+    }
+
+    #[test]
+    fn test_board_cells() {
+        let mut game = Acquire::from_seed(2, &Options::default());
+        game.grid.place(tile!("B2"));
+        game.grid.place(tile!("B3"));
+        game.grid.fill_chain(tile!("B2"), Chain::Festival);
+        game.grid.place(tile!("E5"));
+
+        let cells = game.board_cells();
+        assert_eq!(cells.len(), 12 * 9);
+
+        let b2: Tile = tile!("B2");
+        let e5: Tile = tile!("E5");
+
+        // row by row, so B2 is the second cell of the second row
+        let festival = cells[12 + 1];
+        assert_eq!(festival.point, b2.0);
+        assert_eq!(festival.kind, CellKind::Chain(Chain::Festival));
+        assert_eq!(festival.color, Some(Chain::Festival.color()));
+        assert_eq!(festival.label, Some('F'));
+
+        let lone = cells.iter().find(|cell| cell.point == e5.0).unwrap();
+        assert_eq!((lone.kind, lone.color, lone.label), (CellKind::NoChain, None, None));
+
+        let empty = cells[0];
+        assert_eq!((empty.kind, empty.color, empty.label), (CellKind::Empty(Legality::Legal), None, None));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_spectator_view_hides_tiles() {
+        let game = Acquire::from_seed(2, &Options::default());
+        let json = serde_json::to_value(game.spectator_view()).unwrap();
+
+        // there's nowhere for a tile to be, only the hand sizes
+        assert!(json.get("hand").is_none());
+        assert!(json.get("tiles").is_none());
+        for player in json["players"].as_array().unwrap() {
+            assert!(player.get("tiles").is_none());
+            assert_eq!(player["num_tiles"], 6);
+        }
+
+        let hand_tiles = game.players.iter().flat_map(|player| &player.tiles).map(|tile| format!("\"{tile}\""));
+        let text = json.to_string();
+        for tile in hand_tiles {
+            assert!(!text.contains(&tile));
+        }
+    }
+}
@@ -31,7 +31,7 @@ fn main() {
 
         let action = game.step().unwrap();
 
-        println!("{}", action);
+        println!("{}", game.state.format_action(&action));
         println!("{}", game.state);
     }
 }